@@ -0,0 +1,912 @@
+//! Importing Anki decks, from `.apkg` packages or from Anki's "Notes in
+//! Plain Text" export. An import reads the notes first; the learner then
+//! says which field holds which language before the deck is written.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{env, fmt};
+
+use rusqlite::{Connection, OpenFlags};
+use ruzstd::decoding::StreamingDecoder;
+use zip::ZipArchive;
+
+use crate::Meaning;
+use crate::deck::Deck;
+use crate::grammar::Translation;
+use crate::language;
+use crate::scheduler::{self, Grade};
+use crate::storage::Progress;
+
+#[derive(Debug)]
+pub enum ImportError {
+	Io(PathBuf, io::Error),
+	Package(PathBuf, zip::result::ZipError),
+	Database(PathBuf, rusqlite::Error),
+	Format(PathBuf, String),
+}
+
+impl fmt::Display for ImportError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ImportError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+			ImportError::Package(path, e) => write!(f, "{}: {}", path.display(), e),
+			ImportError::Database(path, e) => write!(f, "{}: {}", path.display(), e),
+			ImportError::Format(path, message) => write!(f, "{}: {}", path.display(), message),
+		}
+	}
+}
+
+impl std::error::Error for ImportError {}
+
+/// What a note field becomes in the imported deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+	Skip,
+	/// The word in this language. A `[sound:…]` in the field becomes its
+	/// recording.
+	Language(String),
+	/// The meaning's note, shown after it is answered.
+	Note,
+	/// The meaning's picture: an `<img>` in the field, or its text as an
+	/// emoji.
+	Picture,
+}
+
+/// One review from Anki's history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Review {
+	/// Seconds since the Unix epoch.
+	pub at: u64,
+	pub grade: Grade,
+}
+
+/// An Anki note: its field values as Anki stores them, HTML included.
+#[derive(Debug, Clone)]
+pub struct Note {
+	/// Values by field of [`Export::fields`]; empty for fields of other note
+	/// types.
+	pub fields: Vec<String>,
+	/// Reviews of every card of the note, oldest first.
+	pub reviews: Vec<Review>,
+}
+
+/// Where media referenced by the notes can be found.
+#[derive(Debug, Clone)]
+enum Media {
+	/// Inside the package, by the name notes use and the zip entry holding
+	/// it. Current packages compress each entry with zstd.
+	Package { path: PathBuf, entries: HashMap<String, String>, compressed: bool },
+	/// Next to a text export, if anywhere.
+	Directory(PathBuf),
+}
+
+/// Notes read from an Anki export, not yet made into a deck.
+#[derive(Debug, Clone)]
+pub struct Export {
+	/// Name of the Anki deck, or of the file it was read from.
+	pub name: String,
+	/// Field names of every note type in the export, each once.
+	pub fields: Vec<String>,
+	pub notes: Vec<Note>,
+	media: Media,
+}
+
+/// A deck written from an [`Export`].
+#[derive(Debug, Clone)]
+pub struct Imported {
+	pub deck: Deck,
+	/// Notes that gave no word in any language, or the same meaning as an
+	/// earlier note.
+	pub skipped: usize,
+	/// Recordings and images the notes refer to but the export lacks.
+	pub missing_media: usize,
+	/// Anki reviews by meaning of `deck`.
+	reviews: Vec<Vec<Review>>,
+}
+
+impl Export {
+	/// Reads an `.apkg` package, or a text export for any other extension.
+	pub fn read(path: &Path) -> Result<Self, ImportError> {
+		if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("apkg")) {
+			read_package(path)
+		} else {
+			let text = fs::read_to_string(path).map_err(|e| ImportError::Io(path.to_path_buf(), e))?;
+			Ok(read_text(&text, path))
+		}
+	}
+
+	/// A first guess at what each field holds, from its name: fields
+	/// named after a language hold words in it, and "Notes", "Picture" and
+	/// the like are recognised.
+	pub fn guess_fields(&self) -> Vec<Field> {
+		self.fields
+			.iter()
+			.map(|name| {
+				let lower = name.trim().to_lowercase();
+				if let Some(code) = language::code_for_name(&lower) {
+					Field::Language(code.to_string())
+				} else if ["note", "notes", "extra", "comment"].contains(&lower.as_str()) {
+					Field::Note
+				} else if ["picture", "image", "emoji"].contains(&lower.as_str()) {
+					Field::Picture
+				} else {
+					Field::Skip
+				}
+			})
+			.collect()
+	}
+
+	/// The first value of field `index` that is not empty, as plain text,
+	/// to show what the field holds.
+	pub fn sample(&self, index: usize) -> String {
+		self.notes
+			.iter()
+			.filter_map(|note| note.fields.get(index))
+			.map(|value| words_of(value))
+			.find(|text| !text.is_empty())
+			.unwrap_or_default()
+	}
+
+	/// Writes the notes as a deck in `dir`, with `fields` saying what each
+	/// field of [`Export::fields`] becomes. Media the deck uses is copied
+	/// into a directory beside it. An existing deck is never overwritten;
+	/// the new one gets a numbered file name instead.
+	pub fn import(&self, fields: &[Field], dir: &Path) -> Result<Imported, ImportError> {
+		if !fields.iter().any(|f| matches!(f, Field::Language(_))) {
+			return Err(ImportError::Format(dir.to_path_buf(), "no field is given a language".to_string()));
+		}
+		fs::create_dir_all(dir).map_err(|e| ImportError::Io(dir.to_path_buf(), e))?;
+		let stem = free_stem(dir, &file_stem(&self.name));
+		let path = dir.join(format!("{}.toml", stem));
+		let media_dir = dir.join(&stem);
+
+		let mut meanings: Vec<Meaning> = Vec::new();
+		let mut reviews: Vec<Vec<Review>> = Vec::new();
+		let mut media: BTreeSet<String> = BTreeSet::new();
+		let mut skipped = 0;
+		for note in &self.notes {
+			let mut meaning = Meaning::new();
+			let mut words = 0;
+			let mut image = None;
+			for (field, value) in fields.iter().zip(&note.fields) {
+				match field {
+					Field::Skip => {}
+					Field::Language(code) => {
+						let text = words_of(value);
+						if text.is_empty() {
+							continue;
+						}
+						let mut word = Translation::parse(code, &text);
+						if let Some(sound) = sound_of(value) {
+							word.audio = Some(media_dir.join(&sound));
+							media.insert(sound);
+						}
+						meaning.add_word(code, word);
+						words += 1;
+					}
+					Field::Note => {
+						let text = clean_html(value);
+						if !text.is_empty() {
+							meaning.set_note(&text);
+						}
+					}
+					Field::Picture => match image_of(value) {
+						Some(file) => image = Some(file),
+						None => {
+							let emoji = words_of(value);
+							if !emoji.is_empty() {
+								meaning.set_emoji(&emoji);
+							}
+						}
+					},
+				}
+			}
+			if let Some(file) = image {
+				meaning.set_image(media_dir.join(&file));
+				media.insert(file);
+			}
+			if words == 0 || meanings.contains(&meaning) {
+				skipped += 1;
+				continue;
+			}
+			meanings.push(meaning);
+			reviews.push(note.reviews.clone());
+		}
+
+		let codes: Vec<&str> = fields
+			.iter()
+			.filter_map(|f| match f {
+				Field::Language(code) => Some(code.as_str()),
+				_ => None,
+			})
+			.collect();
+		// The deck must not point at media that never arrived.
+		let missing = self.copy_media(&media, &media_dir)?;
+		let arrived = |path: &Path| !path.file_name().is_some_and(|name| missing.contains(&*name.to_string_lossy()));
+		for meaning in &mut meanings {
+			for word in meaning.translations.values_mut() {
+				word.audio = word.audio.take().filter(|audio| arrived(audio));
+			}
+			if meaning.image.as_deref().is_some_and(|image| !arrived(image)) {
+				meaning.image = None;
+			}
+		}
+
+		let deck = Deck { name: self.name.clone(), path: path.clone(), meanings };
+		fs::write(&path, deck_toml(&deck, &codes, dir)).map_err(|e| ImportError::Io(path.clone(), e))?;
+		Ok(Imported { deck, skipped, missing_media: missing.len(), reviews })
+	}
+
+	/// Copies the media files named `names` into `to`, returning the names
+	/// of those that could not be found.
+	fn copy_media(&self, names: &BTreeSet<String>, to: &Path) -> Result<BTreeSet<String>, ImportError> {
+		let mut missing = BTreeSet::new();
+		if names.is_empty() {
+			return Ok(missing);
+		}
+		fs::create_dir_all(to).map_err(|e| ImportError::Io(to.to_path_buf(), e))?;
+		match &self.media {
+			Media::Package { path, entries, compressed } => {
+				let file = File::open(path).map_err(|e| ImportError::Io(path.clone(), e))?;
+				let mut archive = ZipArchive::new(file).map_err(|e| ImportError::Package(path.clone(), e))?;
+				for name in names {
+					let entry = entries.get(name).and_then(|entry| archive.by_name(entry).ok());
+					let Some(mut entry) = entry.and_then(|entry| unpacked(entry, *compressed)) else {
+						missing.insert(name.clone());
+						continue;
+					};
+					let dest = to.join(name);
+					let mut out = File::create(&dest).map_err(|e| ImportError::Io(dest.clone(), e))?;
+					io::copy(&mut entry, &mut out).map_err(|e| ImportError::Io(dest.clone(), e))?;
+				}
+			}
+			Media::Directory(dir) => {
+				for name in names {
+					let dest = to.join(name);
+					if fs::copy(dir.join(name), &dest).is_err() {
+						missing.insert(name.clone());
+					}
+				}
+			}
+		}
+		Ok(missing)
+	}
+}
+
+impl Imported {
+	/// Number of Anki reviews of the imported meanings.
+	pub fn review_count(&self) -> usize {
+		self.reviews.iter().map(Vec::len).sum()
+	}
+
+	/// Replays the Anki reviews into `progress`, as answers given when
+	/// shown the word in `learning` and asked for it in `known`. Corvid has
+	/// one card for that direction, so the reviews of every Anki card of a
+	/// note count towards it. Cards already practised in Corvid keep their
+	/// own history. Returns the number of reviews replayed.
+	pub fn import_history(&self, progress: &mut Progress, known: &str, learning: &str) -> usize {
+		let mut replayed = 0;
+		for (meaning, reviews) in self.deck.meanings.iter().zip(&self.reviews) {
+			let (Some(known_text), Some(learning_text)) = (meaning.get_translation(known), meaning.get_translation(learning))
+			else {
+				continue;
+			};
+			let card = scheduler::card_key(known, &known_text, learning, &learning_text);
+			if reviews.is_empty() || progress.scheduler.state(&card).is_some() {
+				continue;
+			}
+			for review in reviews {
+				progress.import_review(&card, review.grade, review.at);
+			}
+			replayed += reviews.len();
+		}
+		replayed
+	}
+}
+
+/// Anki's answer buttons, 1 to 4, as grades. Anything else is a manual
+/// reschedule rather than an answer.
+fn grade_of(ease: i64) -> Option<Grade> {
+	match ease {
+		1 => Some(Grade::Again),
+		2 => Some(Grade::Hard),
+		3 | 4 => Some(Grade::Good),
+		_ => None,
+	}
+}
+
+/// Deletes the file it names when dropped.
+struct TempFile(PathBuf);
+
+impl TempFile {
+	/// Creates an empty file under a random name in the temporary
+	/// directory. The name must not exist yet, so neither another import
+	/// nor a planted symlink can get in the way.
+	fn create() -> io::Result<(Self, File)> {
+		loop {
+			let path = env::temp_dir().join(format!("corvid-import-{:016x}.anki2", rand::random::<u64>()));
+			match OpenOptions::new().write(true).create_new(true).open(&path) {
+				Ok(file) => return Ok((TempFile(path), file)),
+				Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+				Err(e) => return Err(e),
+			}
+		}
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.0);
+	}
+}
+
+fn read_package(path: &Path) -> Result<Export, ImportError> {
+	let package_error = |e| ImportError::Package(path.to_path_buf(), e);
+	let format_error = |message: &str| ImportError::Format(path.to_path_buf(), message.to_string());
+	let file = File::open(path).map_err(|e| ImportError::Io(path.to_path_buf(), e))?;
+	let mut archive = ZipArchive::new(file).map_err(package_error)?;
+
+	// Packages from Anki 2.1.50 on hold a zstd-compressed collection, next
+	// to an old-style one that only asks for a newer Anki. Older packages,
+	// and newer ones exported for older versions, hold just the old style.
+	let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+	let has = |name: &str| names.iter().any(|n| n == name);
+	let (collection, compressed) = if has("collection.anki21b") {
+		("collection.anki21b", true)
+	} else if has("collection.anki21") {
+		("collection.anki21", false)
+	} else if has("collection.anki2") {
+		("collection.anki2", false)
+	} else {
+		return Err(format_error("not an Anki package: it has no collection"));
+	};
+
+	let temp = {
+		let entry = archive.by_name(collection).map_err(package_error)?;
+		let mut entry = unpacked(entry, compressed).ok_or_else(|| format_error("the collection cannot be decompressed"))?;
+		let (temp, mut out) = TempFile::create().map_err(|e| ImportError::Io(env::temp_dir(), e))?;
+		io::copy(&mut entry, &mut out).map_err(|e| ImportError::Io(temp.0.clone(), e))?;
+		temp
+	};
+	let mut export = read_collection(&temp.0).map_err(|e| match e {
+		ImportError::Database(_, e) => ImportError::Database(path.to_path_buf(), e),
+		ImportError::Format(_, message) => ImportError::Format(path.to_path_buf(), message),
+		e => e,
+	})?;
+	if export.name.is_empty() {
+		export.name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+	}
+
+	// The media list maps the numbered zip entries to the names notes use.
+	// Old packages give it as JSON, current ones as compressed protobuf.
+	let mut entries = HashMap::new();
+	if has("media") {
+		let mut bytes = Vec::new();
+		let media = archive.by_name("media").map_err(package_error)?;
+		unpacked(media, compressed)
+			.ok_or_else(|| format_error("the media list cannot be decompressed"))?
+			.read_to_end(&mut bytes)
+			.map_err(|e| ImportError::Io(path.to_path_buf(), e))?;
+		let list = if compressed {
+			media_entries(&bytes)
+		} else {
+			serde_json::from_slice::<HashMap<String, String>>(&bytes).ok().map(|list| list.into_iter().collect())
+		};
+		for (entry, name) in list.ok_or_else(|| format_error("the media list cannot be read"))? {
+			if let Some(name) = safe_name(&name) {
+				entries.insert(name, entry);
+			}
+		}
+	}
+	export.media = Media::Package { path: path.to_path_buf(), entries, compressed };
+	Ok(export)
+}
+
+/// `entry` of a package, decompressed when the package compresses its
+/// entries. `None` when it is not zstd data after all.
+fn unpacked<'a>(entry: impl Read + 'a, compressed: bool) -> Option<Box<dyn Read + 'a>> {
+	if !compressed {
+		return Some(Box::new(entry));
+	}
+	StreamingDecoder::new(entry).ok().map(|decoder| Box::new(decoder) as Box<dyn Read>)
+}
+
+/// Reads the notes, field names and review history of an Anki collection
+/// database. The deck name is the one most cards are in, or empty.
+fn read_collection(path: &Path) -> Result<Export, ImportError> {
+	let db_error = |e| ImportError::Database(path.to_path_buf(), e);
+	let db = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(db_error)?;
+
+	// Collections from Anki 2.1.28 on keep note types and decks in tables
+	// of their own; older ones keep them as JSON in `col`.
+	let tables: bool = db
+		.query_row("SELECT count(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'notetypes'", [], |row| {
+			row.get(0)
+		})
+		.map_err(db_error)?;
+	let (deck_names, note_types) = if tables {
+		read_tables(&db).map_err(db_error)?
+	} else {
+		read_col(&db, path)?
+	};
+
+	// Each note type's fields, by their place in the shared field list.
+	let mut fields: Vec<String> = Vec::new();
+	let mut slots: HashMap<i64, Vec<usize>> = HashMap::new();
+	for (id, mut flds) in note_types {
+		flds.sort();
+		let model_slots = flds
+			.into_iter()
+			.map(|(_, name)| match fields.iter().position(|f| *f == name) {
+				Some(i) => i,
+				None => {
+					fields.push(name);
+					fields.len() - 1
+				}
+			})
+			.collect();
+		slots.insert(id, model_slots);
+	}
+
+	let mut reviews: HashMap<i64, Vec<Review>> = HashMap::new();
+	let mut statement = db
+		.prepare("SELECT cards.nid, revlog.id, revlog.ease FROM revlog JOIN cards ON cards.id = revlog.cid ORDER BY revlog.id")
+		.map_err(db_error)?;
+	let rows = statement
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?)))
+		.map_err(db_error)?;
+	for row in rows {
+		let (note, millis, ease) = row.map_err(db_error)?;
+		if let Some(grade) = grade_of(ease) {
+			reviews.entry(note).or_default().push(Review { at: (millis / 1000).max(0) as u64, grade });
+		}
+	}
+
+	let mut notes = Vec::new();
+	let mut statement = db.prepare("SELECT id, mid, flds FROM notes ORDER BY id").map_err(db_error)?;
+	let rows = statement
+		.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?)))
+		.map_err(db_error)?;
+	for row in rows {
+		let (id, model, flds) = row.map_err(db_error)?;
+		let Some(model_slots) = slots.get(&model) else { continue };
+		let mut values = vec![String::new(); fields.len()];
+		for (&slot, value) in model_slots.iter().zip(flds.split('\x1f')) {
+			values[slot] = value.to_string();
+		}
+		notes.push(Note { fields: values, reviews: reviews.remove(&id).unwrap_or_default() });
+	}
+
+	let mut statement = db
+		.prepare("SELECT did FROM cards GROUP BY did ORDER BY count(*) DESC")
+		.map_err(db_error)?;
+	let deck_ids = statement.query_map([], |row| row.get::<_, i64>(0)).map_err(db_error)?;
+	let mut name = String::new();
+	for id in deck_ids {
+		let id = id.map_err(db_error)?;
+		if let Some(deck) = deck_names.get(&id)
+			&& deck != "Default"
+		{
+			name = leaf(deck).to_string();
+			break;
+		}
+	}
+
+	Ok(Export { name, fields, notes, media: Media::Directory(PathBuf::new()) })
+}
+
+/// Deck names by id, and the fields of each note type as (place, name).
+type Structure = (HashMap<i64, String>, BTreeMap<i64, Vec<(i64, String)>>);
+
+/// Reads the decks and note types of a collection that keeps them as JSON
+/// in its `col` table.
+fn read_col(db: &Connection, path: &Path) -> Result<Structure, ImportError> {
+	let format_error = |message: &str| ImportError::Format(path.to_path_buf(), message.to_string());
+	let (decks, models): (String, String) = db
+		.query_row("SELECT decks, models FROM col", [], |row| Ok((row.get(0)?, row.get(1)?)))
+		.map_err(|e| ImportError::Database(path.to_path_buf(), e))?;
+	let decks: HashMap<String, serde_json::Value> =
+		serde_json::from_str(&decks).map_err(|_| format_error("the deck list cannot be read"))?;
+	let models: HashMap<String, serde_json::Value> =
+		serde_json::from_str(&models).map_err(|_| format_error("the note types cannot be read"))?;
+
+	let deck_names = decks
+		.into_iter()
+		.filter_map(|(id, deck)| Some((id.parse().ok()?, deck["name"].as_str()?.to_string())))
+		.collect();
+	let note_types = models
+		.into_iter()
+		.filter_map(|(id, model)| {
+			let fields = model["flds"]
+				.as_array()?
+				.iter()
+				.map(|f| (f["ord"].as_i64().unwrap_or_default(), f["name"].as_str().unwrap_or_default().to_string()))
+				.collect();
+			Some((id.parse().ok()?, fields))
+		})
+		.collect();
+	Ok((deck_names, note_types))
+}
+
+/// Reads the decks and note types of a collection that keeps them in
+/// tables of their own. Deck names separate their parts with `\x1f`
+/// there rather than `::`.
+fn read_tables(db: &Connection) -> rusqlite::Result<Structure> {
+	let mut deck_names = HashMap::new();
+	let mut statement = db.prepare("SELECT id, name FROM decks")?;
+	for row in statement.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))? {
+		let (id, name) = row?;
+		deck_names.insert(id, name.replace('\x1f', "::"));
+	}
+	let mut note_types: BTreeMap<i64, Vec<(i64, String)>> = BTreeMap::new();
+	let mut statement = db.prepare("SELECT ntid, ord, name FROM fields")?;
+	let rows = statement.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?)))?;
+	for row in rows {
+		let (note_type, ord, name) = row?;
+		note_types.entry(note_type).or_default().push((ord, name));
+	}
+	Ok((deck_names, note_types))
+}
+
+/// Reads the media list of a current package, a protobuf `MediaEntries`.
+/// Its entries are stored in the zip under their index, unless they
+/// give another name in field 255. Returns (zip entry, name) pairs, or
+/// `None` when the list is malformed.
+fn media_entries(bytes: &[u8]) -> Option<Vec<(String, String)>> {
+	let mut list = Vec::new();
+	for (field, value) in protobuf_fields(bytes)? {
+		let (1, Wire::Bytes(entry)) = (field, value) else { continue };
+		let mut name = None;
+		let mut zip_name = list.len().to_string();
+		for (field, value) in protobuf_fields(entry)? {
+			match (field, value) {
+				(1, Wire::Bytes(text)) => name = Some(String::from_utf8(text.to_vec()).ok()?),
+				(255, Wire::Varint(n)) => zip_name = n.to_string(),
+				_ => {}
+			}
+		}
+		list.push((zip_name, name?));
+	}
+	Some(list)
+}
+
+/// A protobuf field value, as far as the media list needs it.
+enum Wire<'a> {
+	Varint(u64),
+	Bytes(&'a [u8]),
+	Fixed,
+}
+
+/// Splits a protobuf message into its fields, in order.
+fn protobuf_fields(mut bytes: &[u8]) -> Option<Vec<(u64, Wire<'_>)>> {
+	fn varint(bytes: &mut &[u8]) -> Option<u64> {
+		let mut value = 0u64;
+		for shift in (0..64).step_by(7) {
+			let (&byte, rest) = bytes.split_first()?;
+			*bytes = rest;
+			value |= u64::from(byte & 0x7f) << shift;
+			if byte & 0x80 == 0 {
+				return Some(value);
+			}
+		}
+		None
+	}
+
+	let mut fields = Vec::new();
+	while !bytes.is_empty() {
+		let key = varint(&mut bytes)?;
+		let value = match key & 7 {
+			0 => Wire::Varint(varint(&mut bytes)?),
+			1 | 5 => {
+				let width = if key & 7 == 1 { 8 } else { 4 };
+				bytes = bytes.get(width..)?;
+				Wire::Fixed
+			}
+			2 => {
+				let len = usize::try_from(varint(&mut bytes)?).ok()?;
+				let (value, rest) = (bytes.get(..len)?, &bytes[len..]);
+				bytes = rest;
+				Wire::Bytes(value)
+			}
+			_ => return None,
+		};
+		fields.push((key >> 3, value));
+	}
+	Some(fields)
+}
+
+/// Reads a "Notes in Plain Text" export. Its `#` header lines can give the
+/// separator, the column names and which columns hold the note type, deck,
+/// tags and id; without column names, columns are numbered.
+pub fn read_text(text: &str, path: &Path) -> Export {
+	let mut separator = '\t';
+	let mut names: Vec<String> = Vec::new();
+	let mut special: HashMap<usize, &str> = HashMap::new();
+	let mut body = text;
+	while let Some(line) = body.strip_prefix('#') {
+		let (header, rest) = line.split_once('\n').unwrap_or((line, ""));
+		body = rest;
+		let Some((key, value)) = header.trim_end_matches('\r').split_once(':') else { continue };
+		match key {
+			"separator" => {
+				separator = match value.to_lowercase().as_str() {
+					"tab" => '\t',
+					"comma" => ',',
+					"semicolon" => ';',
+					"space" => ' ',
+					"pipe" => '|',
+					"colon" => ':',
+					other => other.chars().next().unwrap_or('\t'),
+				}
+			}
+			"columns" => names = value.split(separator).map(str::to_string).collect(),
+			_ => {
+				let label = match key {
+					"notetype column" => "Note type",
+					"deck column" => "Deck",
+					"tags column" => "Tags",
+					"guid column" => "Id",
+					_ => continue,
+				};
+				if let Ok(column) = value.trim().parse::<usize>()
+					&& column > 0
+				{
+					special.insert(column - 1, label);
+				}
+			}
+		}
+	}
+
+	let records = split_records(body, separator);
+	let width = records.iter().map(Vec::len).max().unwrap_or(0).max(names.len());
+	let fields = (0..width)
+		.map(|i| match (names.get(i), special.get(&i)) {
+			(Some(name), _) if !name.trim().is_empty() => name.trim().to_string(),
+			(_, Some(label)) => label.to_string(),
+			_ => format!("Field {}", i + 1),
+		})
+		.collect();
+
+	// The deck most notes are in names the import.
+	let mut deck_counts: BTreeMap<&str, usize> = BTreeMap::new();
+	if let Some((&column, _)) = special.iter().find(|(_, label)| **label == "Deck") {
+		for record in &records {
+			if let Some(deck) = record.get(column) {
+				*deck_counts.entry(leaf(deck)).or_default() += 1;
+			}
+		}
+	}
+	let name = deck_counts
+		.into_iter()
+		.max_by_key(|(_, count)| *count)
+		.map(|(deck, _)| deck.to_string())
+		.unwrap_or_else(|| path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default());
+
+	let notes = records
+		.into_iter()
+		.map(|mut fields| {
+			fields.resize(width, String::new());
+			Note { fields, reviews: Vec::new() }
+		})
+		.collect();
+	let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+	Export { name, fields, notes, media: Media::Directory(dir) }
+}
+
+/// Splits delimited text into records of fields. A field in double quotes
+/// may hold the separator, line breaks and doubled quotes. Blank lines are
+/// skipped.
+fn split_records(text: &str, separator: char) -> Vec<Vec<String>> {
+	let mut records = Vec::new();
+	let mut record = Vec::new();
+	let mut field = String::new();
+	let mut quoted = false;
+	let mut chars = text.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'"' if quoted => {
+				if chars.peek() == Some(&'"') {
+					chars.next();
+					field.push('"');
+				} else {
+					quoted = false;
+				}
+			}
+			'"' if field.is_empty() => quoted = true,
+			c if quoted => field.push(c),
+			c if c == separator => record.push(std::mem::take(&mut field)),
+			'\r' => {}
+			'\n' => {
+				record.push(std::mem::take(&mut field));
+				if record.iter().any(|f| !f.is_empty()) {
+					records.push(std::mem::take(&mut record));
+				}
+				record.clear();
+			}
+			c => field.push(c),
+		}
+	}
+	record.push(field);
+	if record.iter().any(|f| !f.is_empty()) {
+		records.push(record);
+	}
+	records
+}
+
+/// The last part of an Anki deck name: "Spanish" for "Languages::Spanish".
+fn leaf(deck: &str) -> &str {
+	deck.rsplit("::").next().unwrap_or(deck).trim()
+}
+
+/// The text of a field, without markup, media references or line breaks.
+fn words_of(value: &str) -> String {
+	clean_html(value).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The text of a field as lines, without markup or media references.
+fn clean_html(value: &str) -> String {
+	let mut text = String::new();
+	let mut rest = value;
+	while let Some(start) = rest.find(['<', '[']) {
+		text.push_str(&rest[..start]);
+		let after = &rest[start..];
+		let end = if after.starts_with('<') {
+			after.find('>')
+		} else if after.starts_with("[sound:") {
+			after.find(']')
+		} else {
+			None
+		};
+		match end {
+			Some(end) => {
+				let tag = after[..=end].to_lowercase();
+				if ["<br", "</div", "</p", "</li"].iter().any(|t| tag.starts_with(t)) {
+					text.push('\n');
+				}
+				rest = &after[end + 1..];
+			}
+			None => {
+				text.push_str(&after[..1]);
+				rest = &after[1..];
+			}
+		}
+	}
+	text.push_str(rest);
+	decode_entities(&text)
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty())
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+	let mut out = String::new();
+	let mut rest = text;
+	while let Some(start) = rest.find('&') {
+		out.push_str(&rest[..start]);
+		let after = &rest[start..];
+		let decoded = after.find(';').filter(|&end| end <= 10).and_then(|end| {
+			let entity = &after[1..end];
+			let c = match entity {
+				"nbsp" => Some(' '),
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				_ => entity
+					.strip_prefix("#x")
+					.or_else(|| entity.strip_prefix("#X"))
+					.and_then(|hex| u32::from_str_radix(hex, 16).ok())
+					.or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+					.and_then(char::from_u32),
+			};
+			c.map(|c| (c, end))
+		});
+		match decoded {
+			Some((c, end)) => {
+				out.push(c);
+				rest = &after[end + 1..];
+			}
+			None => {
+				out.push('&');
+				rest = &after[1..];
+			}
+		}
+	}
+	out.push_str(rest);
+	out
+}
+
+/// The first `[sound:…]` file of a field.
+fn sound_of(value: &str) -> Option<String> {
+	let start = value.find("[sound:")? + "[sound:".len();
+	let end = value[start..].find(']')? + start;
+	safe_name(&value[start..end])
+}
+
+/// The first `<img src=…>` file of a field.
+fn image_of(value: &str) -> Option<String> {
+	let lower = value.to_lowercase();
+	let tag = lower.find("<img")?;
+	let src = lower[tag..].find("src=")? + tag + "src=".len();
+	let rest = &value[src..];
+	let name = match rest.chars().next()? {
+		quote @ ('"' | '\'') => rest[1..].split(quote).next()?,
+		_ => rest.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next()?,
+	};
+	safe_name(name)
+}
+
+/// `name` when it names a file in the media directory itself. Anything
+/// that could point elsewhere, like "../x" or a URL, is refused.
+fn safe_name(name: &str) -> Option<String> {
+	let name = decode_entities(name.trim());
+	let plain = !name.is_empty()
+		&& name != "."
+		&& name != ".."
+		&& !name.contains(['/', '\\', ':']);
+	plain.then_some(name)
+}
+
+/// A file name for a deck called `name`: lowercase letters and digits,
+/// with underscores between words.
+fn file_stem(name: &str) -> String {
+	let words: Vec<String> = name
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|w| !w.is_empty())
+		.map(str::to_lowercase)
+		.collect();
+	if words.is_empty() { "anki".to_string() } else { words.join("_") }
+}
+
+/// `stem`, or `stem_2`, `stem_3`… if a deck or media directory of that
+/// name is already in `dir`.
+fn free_stem(dir: &Path, stem: &str) -> String {
+	let taken = |s: &str| dir.join(format!("{}.toml", s)).exists() || dir.join(s).exists();
+	if !taken(stem) {
+		return stem.to_string();
+	}
+	(2..).map(|n| format!("{}_{}", stem, n)).find(|s| !taken(s)).unwrap()
+}
+
+/// `deck` in the deck file format, with its languages in the order of
+/// `codes` and media paths relative to `dir`.
+fn deck_toml(deck: &Deck, codes: &[&str], dir: &Path) -> String {
+	let quote = |s: &str| toml::Value::String(s.to_string()).to_string();
+	let path = |p: &Path| quote(&p.strip_prefix(dir).unwrap_or(p).to_string_lossy());
+	let mut out = format!("name = {}\n", quote(&deck.name));
+	for meaning in &deck.meanings {
+		out.push_str("\n[[meaning]]\n");
+		let mut written: Vec<&str> = Vec::new();
+		for &code in codes {
+			if written.contains(&code) {
+				continue;
+			}
+			written.push(code);
+			let Some(word) = meaning.translations.get(code) else { continue };
+			let key = if code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+				code.to_string()
+			} else {
+				quote(code)
+			};
+			match &word.audio {
+				Some(audio) => out.push_str(&format!("{} = {{ text = {}, audio = {} }}\n", key, quote(&word.text), path(audio))),
+				None => out.push_str(&format!("{} = {}\n", key, quote(&word.text))),
+			}
+		}
+		if let Some(note) = meaning.note() {
+			out.push_str(&format!("note = {}\n", quote(note)));
+		}
+		if let Some(image) = meaning.image() {
+			out.push_str(&format!("image = {}\n", path(image)));
+		}
+		if let Some(emoji) = meaning.emoji() {
+			out.push_str(&format!("emoji = {}\n", quote(emoji)));
+		}
+	}
+	out
+}
@@ -0,0 +1,556 @@
+//! Present and simple-past conjugation for Spanish, French and German.
+//!
+//! Regular paradigms are built from the infinitive, but only for verbs
+//! known to follow them: listed ones, and endings that are regular
+//! throughout, such as French -er or German -ieren. Irregular verbs are
+//! described by override tables that replace some or all of the six forms,
+//! and prefixed verbs (revenir, erhalten, ankommen) reuse the table of
+//! their base verb. Any other verb has no forms, rather than made-up ones.
+
+use crate::language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Person {
+	First,
+	Second,
+	Third,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Number {
+	Singular,
+	Plural,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tense {
+	Present,
+	/// The simple past: imperfecto, imparfait, Präteritum.
+	Imperfect,
+}
+
+pub const TENSES: [Tense; 2] = [Tense::Present, Tense::Imperfect];
+
+/// One of the six person/number slots, in the usual table order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+	pub person: Person,
+	pub number: Number,
+}
+
+pub const SLOTS: [Slot; 6] = [
+	Slot { person: Person::First, number: Number::Singular },
+	Slot { person: Person::Second, number: Number::Singular },
+	Slot { person: Person::Third, number: Number::Singular },
+	Slot { person: Person::First, number: Number::Plural },
+	Slot { person: Person::Second, number: Number::Plural },
+	Slot { person: Person::Third, number: Number::Plural },
+];
+
+impl Slot {
+	fn index(self) -> usize {
+		let person = match self.person {
+			Person::First => 0,
+			Person::Second => 1,
+			Person::Third => 2,
+		};
+		match self.number {
+			Number::Singular => person,
+			Number::Plural => person + 3,
+		}
+	}
+}
+
+pub fn pronoun(lang: &str, slot: Slot) -> &'static str {
+	let table: [&str; 6] = match language::base(lang) {
+		"es" => ["yo", "tú", "él/ella", "nosotros", "vosotros", "ellos/ellas"],
+		"fr" => ["je", "tu", "il/elle", "nous", "vous", "ils/elles"],
+		"de" => ["ich", "du", "er/sie/es", "wir", "ihr", "sie"],
+		_ => ["1sg", "2sg", "3sg", "1pl", "2pl", "3pl"],
+	};
+	table[slot.index()]
+}
+
+pub fn tense_name(lang: &str, tense: Tense) -> &'static str {
+	match (language::base(lang), tense) {
+		("es", Tense::Present) => "presente",
+		("es", Tense::Imperfect) => "imperfecto",
+		("fr", Tense::Present) => "présent",
+		("fr", Tense::Imperfect) => "imparfait",
+		("de", Tense::Present) => "Präsens",
+		("de", Tense::Imperfect) => "Präteritum",
+		(_, Tense::Present) => "present",
+		(_, Tense::Imperfect) => "past",
+	}
+}
+
+/// The conjugated verb without its subject pronoun, e.g. "tenemos",
+/// "me souviens" or "komme an". `None` when `infinitive` is not a verb
+/// Corvid can conjugate in `lang`.
+pub fn conjugate(lang: &str, infinitive: &str, tense: Tense, slot: Slot) -> Option<String> {
+	let infinitive = infinitive.trim().to_lowercase();
+	if infinitive.contains(char::is_whitespace) && !is_reflexive(lang, &infinitive) {
+		return None;
+	}
+	let forms = match language::base(lang) {
+		"es" => spanish(&infinitive, tense)?,
+		"fr" => french(&infinitive, tense)?,
+		"de" => german(&infinitive, tense)?,
+		_ => return None,
+	};
+	Some(forms[slot.index()].clone())
+}
+
+/// Every form of `infinitive` in the drilled tenses, sorted and without
+/// repeats.
+pub fn all_forms(lang: &str, infinitive: &str) -> Vec<String> {
+	let mut forms: Vec<String> = TENSES
+		.into_iter()
+		.flat_map(|t| SLOTS.into_iter().map(move |s| (t, s)))
+		.filter_map(|(t, s)| conjugate(lang, infinitive, t, s))
+		.collect();
+	forms.sort();
+	forms.dedup();
+	forms
+}
+
+type Forms = [String; 6];
+type Overrides = [Option<&'static str>; 6];
+
+fn forms(stem: &str, endings: [&str; 6]) -> Forms {
+	endings.map(|e| format!("{}{}", stem, e))
+}
+
+fn apply(mut forms: Forms, overrides: &Overrides) -> Forms {
+	for (form, o) in forms.iter_mut().zip(overrides) {
+		if let Some(o) = o {
+			*form = o.to_string();
+		}
+	}
+	forms
+}
+
+fn lookup<'a>(table: &'a [(&str, Overrides)], verb: &str) -> Option<&'a Overrides> {
+	table.iter().find(|(v, _)| *v == verb).map(|(_, o)| o)
+}
+
+fn is_reflexive(lang: &str, infinitive: &str) -> bool {
+	match language::base(lang) {
+		"fr" => infinitive.starts_with("se ") || infinitive.starts_with("s’") || infinitive.starts_with("s'"),
+		_ => false,
+	}
+}
+
+fn starts_with_vowel(word: &str) -> bool {
+	word.starts_with(|c: char| "aeiouhâàéèêëîïôûœ".contains(c))
+}
+
+// Spanish
+
+const ES_PRESENT: &[(&str, Overrides)] = &[
+	("ser", [Some("soy"), Some("eres"), Some("es"), Some("somos"), Some("sois"), Some("son")]),
+	("estar", [Some("estoy"), Some("estás"), Some("está"), None, None, Some("están")]),
+	("ir", [Some("voy"), Some("vas"), Some("va"), Some("vamos"), Some("vais"), Some("van")]),
+	("dar", [Some("doy"), None, None, None, Some("dais"), None]),
+	("ver", [Some("veo"), None, None, None, Some("veis"), None]),
+	("tener", [Some("tengo"), Some("tienes"), Some("tiene"), None, None, Some("tienen")]),
+	("venir", [Some("vengo"), Some("vienes"), Some("viene"), None, None, Some("vienen")]),
+	("decir", [Some("digo"), Some("dices"), Some("dice"), None, None, Some("dicen")]),
+	("seguir", [Some("sigo"), Some("sigues"), Some("sigue"), None, None, Some("siguen")]),
+	("hacer", [Some("hago"), None, None, None, None, None]),
+	("poner", [Some("pongo"), None, None, None, None, None]),
+	("salir", [Some("salgo"), None, None, None, None, None]),
+	("saber", [Some("sé"), None, None, None, None, None]),
+	("conocer", [Some("conozco"), None, None, None, None, None]),
+	("parecer", [Some("parezco"), None, None, None, None, None]),
+	("aparecer", [Some("aparezco"), None, None, None, None, None]),
+	("producir", [Some("produzco"), None, None, None, None, None]),
+];
+
+/// Stem-changing verbs: the last `from` of the stem becomes `to` in the
+/// stressed forms (yo, tú, él, ellos).
+const ES_STEM_CHANGES: &[(&str, &str, &str)] = &[
+	("pensar", "e", "ie"),
+	("empezar", "e", "ie"),
+	("querer", "e", "ie"),
+	("entender", "e", "ie"),
+	("perder", "e", "ie"),
+	("sentir", "e", "ie"),
+	("preferir", "e", "ie"),
+	("encontrar", "o", "ue"),
+	("contar", "o", "ue"),
+	("recordar", "o", "ue"),
+	("volver", "o", "ue"),
+	("poder", "o", "ue"),
+	("dormir", "o", "ue"),
+	("pedir", "e", "i"),
+	("repetir", "e", "i"),
+	("servir", "e", "i"),
+];
+
+/// Verbs that follow the -ar, -er or -ir paradigm throughout.
+const ES_REGULAR: &[&str] = &[
+	"hablar", "trabajar", "llamar", "llegar", "llevar", "mirar", "pasar", "quedar", "tomar", "terminar", "dejar",
+	"entrar", "esperar", "buscar", "necesitar", "comprar", "cocinar", "estudiar", "escuchar", "ayudar", "bailar",
+	"cantar", "caminar", "cenar", "desayunar", "enseñar", "ganar", "gastar", "lavar", "limpiar", "mandar", "nadar",
+	"olvidar", "preguntar", "preparar", "regresar", "tocar", "usar", "viajar", "visitar", "amar", "cambiar",
+	"aprender", "beber", "comer", "comprender", "correr", "creer", "deber", "leer", "meter", "responder", "vender",
+	"abrir", "asistir", "compartir", "decidir", "describir", "escribir", "existir", "ocurrir", "partir", "permitir",
+	"recibir", "subir", "vivir",
+];
+
+const ES_IMPERFECT: &[(&str, Overrides)] = &[
+	("ser", [Some("era"), Some("eras"), Some("era"), Some("éramos"), Some("erais"), Some("eran")]),
+	("ir", [Some("iba"), Some("ibas"), Some("iba"), Some("íbamos"), Some("ibais"), Some("iban")]),
+	("ver", [Some("veía"), Some("veías"), Some("veía"), Some("veíamos"), Some("veíais"), Some("veían")]),
+];
+
+fn spanish(infinitive: &str, tense: Tense) -> Option<Forms> {
+	if let Some(verb) = infinitive.strip_suffix("se") {
+		let mut forms = spanish(verb, tense)?;
+		for (form, pronoun) in forms.iter_mut().zip(["me", "te", "se", "nos", "os", "se"]) {
+			*form = format!("{} {}", pronoun, form);
+		}
+		return Some(forms);
+	}
+
+	let known = lookup(ES_PRESENT, infinitive).is_some()
+		|| ES_STEM_CHANGES.iter().any(|(v, _, _)| *v == infinitive)
+		|| ES_REGULAR.contains(&infinitive);
+	if !known {
+		return None;
+	}
+
+	let (stem, class) = if let Some(s) = infinitive.strip_suffix("ar") {
+		(s, 'a')
+	} else if let Some(s) = infinitive.strip_suffix("er") {
+		(s, 'e')
+	} else if let Some(s) = infinitive.strip_suffix("ir").or_else(|| infinitive.strip_suffix("ír")) {
+		(s, 'i')
+	} else {
+		return None;
+	};
+	if stem.is_empty() && infinitive != "ir" {
+		return None;
+	}
+
+	match tense {
+		Tense::Present => {
+			let mut f = match class {
+				'a' => forms(stem, ["o", "as", "a", "amos", "áis", "an"]),
+				'e' => forms(stem, ["o", "es", "e", "emos", "éis", "en"]),
+				_ => forms(stem, ["o", "es", "e", "imos", "ís", "en"]),
+			};
+			if let Some((_, from, to)) = ES_STEM_CHANGES.iter().find(|(v, _, _)| *v == infinitive)
+				&& let Some(pos) = stem.rfind(from)
+			{
+				let changed = format!("{}{}{}", &stem[..pos], to, &stem[pos + from.len()..]);
+				for i in [0, 1, 2, 5] {
+					f[i] = format!("{}{}", changed, &f[i][stem.len()..]);
+				}
+			}
+			Some(match lookup(ES_PRESENT, infinitive) {
+				Some(o) => apply(f, o),
+				None => f,
+			})
+		}
+		Tense::Imperfect => {
+			let f = match class {
+				'a' => forms(stem, ["aba", "abas", "aba", "ábamos", "abais", "aban"]),
+				_ => forms(stem, ["ía", "ías", "ía", "íamos", "íais", "ían"]),
+			};
+			Some(match lookup(ES_IMPERFECT, infinitive) {
+				Some(o) => apply(f, o),
+				None => f,
+			})
+		}
+	}
+}
+
+// French
+
+const FR_PRESENT: &[(&str, Overrides)] = &[
+	("être", [Some("suis"), Some("es"), Some("est"), Some("sommes"), Some("êtes"), Some("sont")]),
+	("avoir", [Some("ai"), Some("as"), Some("a"), Some("avons"), Some("avez"), Some("ont")]),
+	("aller", [Some("vais"), Some("vas"), Some("va"), Some("allons"), Some("allez"), Some("vont")]),
+	("faire", [Some("fais"), Some("fais"), Some("fait"), Some("faisons"), Some("faites"), Some("font")]),
+	("dire", [Some("dis"), Some("dis"), Some("dit"), Some("disons"), Some("dites"), Some("disent")]),
+	("voir", [Some("vois"), Some("vois"), Some("voit"), Some("voyons"), Some("voyez"), Some("voient")]),
+	("croire", [Some("crois"), Some("crois"), Some("croit"), Some("croyons"), Some("croyez"), Some("croient")]),
+	("savoir", [Some("sais"), Some("sais"), Some("sait"), Some("savons"), Some("savez"), Some("savent")]),
+	("vouloir", [Some("veux"), Some("veux"), Some("veut"), Some("voulons"), Some("voulez"), Some("veulent")]),
+	("pouvoir", [Some("peux"), Some("peux"), Some("peut"), Some("pouvons"), Some("pouvez"), Some("peuvent")]),
+	("devoir", [Some("dois"), Some("dois"), Some("doit"), Some("devons"), Some("devez"), Some("doivent")]),
+	("recevoir", [Some("reçois"), Some("reçois"), Some("reçoit"), Some("recevons"), Some("recevez"), Some("reçoivent")]),
+	("mettre", [Some("mets"), Some("mets"), Some("met"), Some("mettons"), Some("mettez"), Some("mettent")]),
+	("prendre", [Some("prends"), Some("prends"), Some("prend"), Some("prenons"), Some("prenez"), Some("prennent")]),
+	("venir", [Some("viens"), Some("viens"), Some("vient"), Some("venons"), Some("venez"), Some("viennent")]),
+	("tenir", [Some("tiens"), Some("tiens"), Some("tient"), Some("tenons"), Some("tenez"), Some("tiennent")]),
+	("sortir", [Some("sors"), Some("sors"), Some("sort"), Some("sortons"), Some("sortez"), Some("sortent")]),
+	("partir", [Some("pars"), Some("pars"), Some("part"), Some("partons"), Some("partez"), Some("partent")]),
+	("sentir", [Some("sens"), Some("sens"), Some("sent"), Some("sentons"), Some("sentez"), Some("sentent")]),
+	("suivre", [Some("suis"), Some("suis"), Some("suit"), Some("suivons"), Some("suivez"), Some("suivent")]),
+	("vivre", [Some("vis"), Some("vis"), Some("vit"), Some("vivons"), Some("vivez"), Some("vivent")]),
+	("écrire", [Some("écris"), Some("écris"), Some("écrit"), Some("écrivons"), Some("écrivez"), Some("écrivent")]),
+	("connaître", [Some("connais"), Some("connais"), Some("connaît"), Some("connaissons"), Some("connaissez"), Some("connaissent")]),
+	("paraître", [Some("parais"), Some("parais"), Some("paraît"), Some("paraissons"), Some("paraissez"), Some("paraissent")]),
+	("produire", [Some("produis"), Some("produis"), Some("produit"), Some("produisons"), Some("produisez"), Some("produisent")]),
+	("appeler", [Some("appelle"), Some("appelles"), Some("appelle"), None, None, Some("appellent")]),
+];
+
+/// -ir verbs conjugated like finir. The other -ir verbs (dormir, ouvrir,
+/// courir) each go their own way.
+const FR_FINIR: &[&str] = &[
+	"finir", "choisir", "réussir", "remplir", "grandir", "grossir", "maigrir", "obéir", "punir", "réfléchir", "rougir",
+	"vieillir", "agir", "bâtir", "établir", "fournir", "guérir", "nourrir", "saisir", "applaudir", "atterrir",
+	"avertir", "démolir", "envahir", "garantir", "pâlir", "ralentir", "rajeunir", "unir", "accomplir", "définir",
+];
+
+/// Irregular verbs whose compounds conjugate the same way.
+const FR_PREFIX_BASES: &[&str] = &["venir", "tenir", "prendre", "mettre", "paraître", "sentir", "faire"];
+
+fn french(infinitive: &str, tense: Tense) -> Option<Forms> {
+	for prefix in ["se ", "s’", "s'"] {
+		if let Some(verb) = infinitive.strip_prefix(prefix) {
+			let mut forms = french(verb, tense)?;
+			for (form, pronoun) in forms.iter_mut().zip(["me", "te", "se", "nous", "vous", "se"]) {
+				*form = if pronoun.len() == 2 && starts_with_vowel(form) {
+					format!("{}’{}", &pronoun[..1], form)
+				} else {
+					format!("{} {}", pronoun, form)
+				};
+			}
+			return Some(forms);
+		}
+	}
+
+	let present = french_present(infinitive)?;
+	match tense {
+		Tense::Present => Some(present),
+		Tense::Imperfect => {
+			let stem = if infinitive == "être" {
+				"ét".to_string()
+			} else {
+				present[3].strip_suffix("ons")?.to_string()
+			};
+			// -cer and -ger verbs keep their soft consonant only before a/o.
+			let soft = stem.strip_suffix('ç').map(|s| format!("{}c", s))
+				.or_else(|| stem.strip_suffix("ge").map(|s| format!("{}g", s)))
+				.unwrap_or_else(|| stem.clone());
+			Some([
+				format!("{}ais", stem),
+				format!("{}ais", stem),
+				format!("{}ait", stem),
+				format!("{}ions", soft),
+				format!("{}iez", soft),
+				format!("{}aient", stem),
+			])
+		}
+	}
+}
+
+fn french_present(infinitive: &str) -> Option<Forms> {
+	if let Some(o) = lookup(FR_PRESENT, infinitive) {
+		return Some(apply(french_endings(infinitive).unwrap_or_default(), o));
+	}
+	for base in FR_PREFIX_BASES {
+		if let Some(prefix) = infinitive.strip_suffix(base).filter(|p| !p.is_empty()) {
+			let o = lookup(FR_PRESENT, base)?;
+			return Some(o.map(|f| format!("{}{}", prefix, f.unwrap_or_default())));
+		}
+	}
+	french_endings(infinitive).filter(|_| french_regular(infinitive))
+}
+
+/// Whether `infinitive` follows the paradigm of its ending: -er verbs
+/// whose stem does not change (unlike acheter, préférer or payer), the
+/// -ir verbs like finir, and the -re verbs like vendre, attendre,
+/// répondre, perdre and mordre.
+fn french_regular(infinitive: &str) -> bool {
+	if let Some(stem) = infinitive.strip_suffix("er") {
+		if stem.ends_with('y') {
+			return false;
+		}
+		let is_vowel = |c: char| "aeiouâàéèêëîïôûù".contains(c);
+		let start = stem
+			.char_indices()
+			.rev()
+			.take_while(|(_, c)| !is_vowel(*c))
+			.last()
+			.map_or(stem.len(), |(i, _)| i);
+		let (head, cluster) = stem.split_at(start);
+		let consonants = cluster.chars().count();
+		// An e or é before a single consonant, or one like ch or br,
+		// turns into è in the stressed forms.
+		let changes = consonants == 1
+			|| ["ch", "gn"].contains(&cluster)
+			|| (consonants == 2 && cluster.ends_with(['l', 'r']));
+		!(changes && head.ends_with(['e', 'é']))
+	} else if infinitive.ends_with("ir") {
+		FR_FINIR.contains(&infinitive)
+	} else {
+		["andre", "endre", "ondre", "erdre", "ordre"].iter().any(|e| infinitive.ends_with(e))
+	}
+}
+
+/// The forms `infinitive` would have if it followed the paradigm of its
+/// ending. -oir verbs have no paradigm to follow.
+fn french_endings(infinitive: &str) -> Option<Forms> {
+	if infinitive.ends_with("oir") {
+		return None;
+	}
+	if let Some(stem) = infinitive.strip_suffix("er") {
+		let mut f = forms(stem, ["e", "es", "e", "ons", "ez", "ent"]);
+		if let Some(s) = stem.strip_suffix('c') {
+			f[3] = format!("{}çons", s);
+		} else if stem.ends_with('g') {
+			f[3] = format!("{}eons", stem);
+		}
+		Some(f)
+	} else if let Some(stem) = infinitive.strip_suffix("ir") {
+		Some(forms(stem, ["is", "is", "it", "issons", "issez", "issent"]))
+	} else {
+		infinitive.strip_suffix("re").map(|stem| forms(stem, ["s", "s", "", "ons", "ez", "ent"]))
+	}
+}
+
+// German
+
+const DE_PRESENT: &[(&str, Overrides)] = &[
+	("sein", [Some("bin"), Some("bist"), Some("ist"), Some("sind"), Some("seid"), Some("sind")]),
+	("haben", [None, Some("hast"), Some("hat"), None, None, None]),
+	("werden", [None, Some("wirst"), Some("wird"), None, None, None]),
+	("wissen", [Some("weiß"), Some("weißt"), Some("weiß"), None, None, None]),
+	("wollen", [Some("will"), Some("willst"), Some("will"), None, None, None]),
+	("sollen", [Some("soll"), Some("sollst"), Some("soll"), None, None, None]),
+	("können", [Some("kann"), Some("kannst"), Some("kann"), None, None, None]),
+	("müssen", [Some("muss"), Some("musst"), Some("muss"), None, None, None]),
+	("sehen", [None, Some("siehst"), Some("sieht"), None, None, None]),
+	("geschehen", [None, Some("geschiehst"), Some("geschieht"), None, None, None]),
+	("geben", [None, Some("gibst"), Some("gibt"), None, None, None]),
+	("nehmen", [None, Some("nimmst"), Some("nimmt"), None, None, None]),
+	("sprechen", [None, Some("sprichst"), Some("spricht"), None, None, None]),
+	("treten", [None, Some("trittst"), Some("tritt"), None, None, None]),
+	("tragen", [None, Some("trägst"), Some("trägt"), None, None, None]),
+	("fahren", [None, Some("fährst"), Some("fährt"), None, None, None]),
+	("halten", [None, Some("hältst"), Some("hält"), None, None, None]),
+	("lassen", [None, Some("lässt"), Some("lässt"), None, None, None]),
+];
+
+/// Präteritum stems of strong and mixed verbs. Mixed verbs (`true`) take
+/// the weak -te endings on their changed stem.
+const DE_PAST: &[(&str, &str, bool)] = &[
+	("sein", "war", false),
+	("haben", "hat", true),
+	("werden", "wurde", false),
+	("wissen", "wuss", true),
+	("wollen", "woll", true),
+	("sollen", "soll", true),
+	("können", "konn", true),
+	("müssen", "muss", true),
+	("denken", "dach", true),
+	("bringen", "brach", true),
+	("kennen", "kann", true),
+	("gehen", "ging", false),
+	("stehen", "stand", false),
+	("sehen", "sah", false),
+	("geschehen", "geschah", false),
+	("geben", "gab", false),
+	("nehmen", "nahm", false),
+	("kommen", "kam", false),
+	("finden", "fand", false),
+	("sprechen", "sprach", false),
+	("treten", "trat", false),
+	("tragen", "trug", false),
+	("fahren", "fuhr", false),
+	("halten", "hielt", false),
+	("lassen", "ließ", false),
+	("bleiben", "blieb", false),
+	("scheinen", "schien", false),
+	("schreiben", "schrieb", false),
+	("rufen", "rief", false),
+	("bitten", "bat", false),
+	("beginnen", "begann", false),
+	("verlieren", "verlor", false),
+];
+
+/// Weak verbs, which also lets prefixed ones like "einkaufen" be split
+/// as well as "ankommen". Verbs in -ieren are all weak and need no entry.
+const DE_WEAK: &[&str] = &[
+	"machen", "kaufen", "hören", "stellen", "sagen", "holen", "zahlen", "zählen", "legen", "arbeiten", "warten",
+	"glauben", "erlauben", "erinnern", "folgen", "fühlen", "leben", "suchen", "enden", "lernen", "spielen",
+	"wohnen", "brauchen", "fragen", "antworten", "kochen", "lachen", "weinen", "tanzen", "reisen", "zeigen",
+	"öffnen", "putzen", "besuchen", "bezahlen", "wandern", "lieben", "malen", "hoffen", "träumen", "schicken",
+];
+
+const DE_SEPARABLE: &[&str] = &[
+	"zurück", "an", "auf", "aus", "ab", "ein", "mit", "vor", "weg", "zu", "los", "nach", "fest", "fern", "her", "hin",
+];
+
+const DE_INSEPARABLE: &[&str] = &["be", "emp", "ent", "er", "ge", "miss", "ver", "zer"];
+
+fn german_known(verb: &str) -> bool {
+	lookup(DE_PRESENT, verb).is_some()
+		|| DE_PAST.iter().any(|(v, _, _)| *v == verb)
+		|| DE_WEAK.contains(&verb)
+		|| verb.ends_with("ieren")
+}
+
+fn german(infinitive: &str, tense: Tense) -> Option<Forms> {
+	if !german_known(infinitive) {
+		for prefix in DE_SEPARABLE {
+			if let Some(base) = infinitive.strip_prefix(prefix).filter(|b| german_known(b)) {
+				return Some(german(base, tense)?.map(|f| format!("{} {}", f, prefix)));
+			}
+		}
+		for prefix in DE_INSEPARABLE {
+			if let Some(base) = infinitive.strip_prefix(prefix).filter(|b| german_known(b)) {
+				return Some(german(base, tense)?.map(|f| format!("{}{}", prefix, f)));
+			}
+		}
+		return None;
+	}
+
+	let (stem, short_plural) = match infinitive.strip_suffix("en") {
+		Some(s) => (s, false),
+		None => (infinitive.strip_suffix('n').unwrap_or(infinitive), true),
+	};
+	let regular = !stem.is_empty()
+		&& (!short_plural || stem.ends_with("er") || stem.ends_with("el"));
+	let dental = stem.ends_with('t') || stem.ends_with('d');
+	let sibilant = stem.ends_with(['s', 'ß', 'z', 'x']);
+
+	match tense {
+		Tense::Present => {
+			let plural = if short_plural { "n" } else { "en" };
+			let f = if !regular {
+				Forms::default()
+			} else if dental {
+				forms(stem, ["e", "est", "et", plural, "et", plural])
+			} else if sibilant {
+				forms(stem, ["e", "t", "t", plural, "t", plural])
+			} else {
+				forms(stem, ["e", "st", "t", plural, "t", plural])
+			};
+			match lookup(DE_PRESENT, infinitive) {
+				Some(o) => Some(apply(f, o)),
+				None if regular => Some(f),
+				None => None,
+			}
+		}
+		Tense::Imperfect => match DE_PAST.iter().find(|(v, _, _)| *v == infinitive) {
+			Some((_, past, true)) => Some(forms(past, ["te", "test", "te", "ten", "tet", "ten"])),
+			Some((_, past, false)) => {
+				let past_dental = past.ends_with('t') || past.ends_with('d');
+				let past_sibilant = past.ends_with(['s', 'ß', 'z']);
+				let du = if past_dental || past_sibilant { "est" } else { "st" };
+				let ihr = if past_dental { "et" } else { "t" };
+				let plural = if past.ends_with('e') { "n" } else { "en" };
+				Some(forms(past, ["", du, "", plural, ihr, plural]))
+			}
+			None if !regular => None,
+			None if dental => Some(forms(stem, ["ete", "etest", "ete", "eten", "etet", "eten"])),
+			None => Some(forms(stem, ["te", "test", "te", "ten", "tet", "ten"])),
+		},
+	}
+}
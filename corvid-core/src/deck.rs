@@ -0,0 +1,200 @@
+use std::{collections::{BTreeMap, BTreeSet, HashMap}, fmt, fs, io, path::{Path, PathBuf}};
+use serde::Deserialize;
+
+use crate::Meaning;
+use crate::grammar::{Gender, Translation};
+
+#[derive(Debug, Clone)]
+pub struct Deck {
+	pub name: String,
+	pub path: PathBuf,
+	pub meanings: Vec<Meaning>,
+}
+
+#[derive(Debug)]
+pub enum DeckError {
+	Io(PathBuf, io::Error),
+	Parse(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for DeckError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DeckError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+			DeckError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+		}
+	}
+}
+
+impl std::error::Error for DeckError {}
+
+/// On-disk layout of a deck:
+///
+/// ```toml
+/// name = "Animals"
+///
+/// [[meaning]]
+/// en = "The dog"
+/// es = "El perro"
+/// fr = { text = "L’oiseau", gender = "m" }
+/// ja = { text = "犬", reading = "いぬ", romanization = "inu" }
+/// de = { text = "Der Hund", audio = "audio/der-hund.ogg" }
+/// note = "Shown after the card is answered."
+/// image = "images/dog.png"
+/// emoji = "🐕"
+/// ```
+///
+/// The article and gender of a noun are read from its text. The table form
+/// overrides them where the text does not tell, as with elided articles.
+/// Audio clips and images are local files, relative to the deck file. The
+/// emoji stands in for the image when there is none.
+#[derive(Deserialize)]
+struct DeckFile {
+	name: String,
+	#[serde(default, rename = "meaning")]
+	meanings: Vec<MeaningEntry>,
+}
+
+#[derive(Deserialize)]
+struct MeaningEntry {
+	note: Option<String>,
+	image: Option<PathBuf>,
+	emoji: Option<String>,
+	#[serde(flatten)]
+	translations: HashMap<String, TranslationEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TranslationEntry {
+	Text(String),
+	Full {
+		text: String,
+		lemma: Option<String>,
+		article: Option<String>,
+		gender: Option<Gender>,
+		reading: Option<String>,
+		romanization: Option<String>,
+		audio: Option<PathBuf>,
+	},
+}
+
+impl TranslationEntry {
+	/// The word in `lang`, with its audio clip found from `dir`.
+	fn into_translation(self, lang: &str, dir: &Path) -> Translation {
+		match self {
+			TranslationEntry::Text(text) => Translation::parse(lang, &text),
+			TranslationEntry::Full { text, lemma, article, gender, reading, romanization, audio } => {
+				let mut t = Translation::parse(lang, &text);
+				if let Some(lemma) = lemma {
+					t.lemma = lemma;
+				}
+				if article.is_some() {
+					t.article = article;
+				}
+				if gender.is_some() {
+					t.gender = gender;
+				}
+				t.reading = reading;
+				t.romanization = romanization;
+				t.audio = audio.map(|audio| dir.join(audio));
+				t
+			}
+		}
+	}
+}
+
+impl Deck {
+	pub fn load(path: &Path) -> Result<Self, DeckError> {
+		let text = fs::read_to_string(path)
+			.map_err(|e| DeckError::Io(path.to_path_buf(), e))?;
+		Self::parse(&text, path)
+	}
+
+	pub fn parse(text: &str, path: &Path) -> Result<Self, DeckError> {
+		let file: DeckFile = toml::from_str(text)
+			.map_err(|e| DeckError::Parse(path.to_path_buf(), e))?;
+		let dir = path.parent().unwrap_or(Path::new(""));
+
+		let meanings = file.meanings
+			.into_iter()
+			.map(|entry| {
+				let mut m = Meaning::new();
+				if let Some(note) = &entry.note {
+					m.set_note(note);
+				}
+				if let Some(image) = entry.image {
+					m.set_image(dir.join(image));
+				}
+				if let Some(emoji) = &entry.emoji {
+					m.set_emoji(emoji);
+				}
+				for (code, entry) in entry.translations {
+					match entry {
+						TranslationEntry::Text(text) => m.add_translation(&code, &text),
+						entry => m.add_word(&code, entry.into_translation(&code, dir)),
+					}
+				}
+				m
+			})
+			.collect();
+
+		Ok(Self {
+			name: file.name,
+			path: path.to_path_buf(),
+			meanings,
+		})
+	}
+
+	/// Codes of every language used in this deck, sorted.
+	pub fn languages(&self) -> Vec<String> {
+		let codes: BTreeSet<&str> = self.meanings.iter().flat_map(|m| m.languages()).collect();
+		codes.into_iter().map(str::to_string).collect()
+	}
+
+	/// Whether at least one meaning can be asked from `source` to `target`.
+	pub fn covers(&self, source: &str, target: &str) -> bool {
+		source != target
+			&& self.meanings
+				.iter()
+				.any(|m| m.word(source).is_some() && m.word(target).is_some())
+	}
+}
+
+/// Loads every `*.toml` deck from `dirs`, sorted by deck name. Decks that
+/// fail to load are returned separately so the caller can report them.
+pub fn load_decks(dirs: &[PathBuf]) -> (Vec<Deck>, Vec<DeckError>) {
+	let mut by_file: BTreeMap<String, Deck> = BTreeMap::new();
+	let mut errors = Vec::new();
+
+	for dir in dirs {
+		let entries = match fs::read_dir(dir) {
+			Ok(entries) => entries,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+			Err(e) => {
+				errors.push(DeckError::Io(dir.clone(), e));
+				continue;
+			}
+		};
+
+		let mut paths: Vec<PathBuf> = entries
+			.filter_map(|e| e.ok().map(|e| e.path()))
+			.filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+			.collect();
+		paths.sort();
+
+		for path in paths {
+			let key = path.file_name().unwrap().to_string_lossy().into_owned();
+			match Deck::load(&path) {
+				Ok(deck) => {
+					by_file.insert(key, deck);
+				}
+				Err(e) => errors.push(e),
+			}
+		}
+	}
+
+	let mut decks: Vec<Deck> = by_file.into_values().collect();
+	decks.sort_by(|a, b| a.name.cmp(&b.name));
+	(decks, errors)
+}
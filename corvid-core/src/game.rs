@@ -0,0 +1,899 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::{Rng, thread_rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{Meaning, Picture};
+use crate::conjugation::{self, Slot, Tense};
+use crate::grammar;
+use crate::matcher::{self, Tolerance, Verdict};
+use crate::scheduler::{self, Grade};
+use crate::storage::Progress;
+
+/// One question of a session, ready to be shown.
+#[derive(Debug, Clone)]
+pub struct Question {
+	/// What the learner is asked about.
+	pub presented_word: String,
+	/// Reading and romanization of `presented_word`, where the deck has them.
+	pub presented_reading: Option<String>,
+	pub presented_romanization: Option<String>,
+	/// Recording of `presented_word`, where the deck has one.
+	pub presented_audio: Option<PathBuf>,
+	/// Picture of the meaning asked about, where the deck has one.
+	pub presented_picture: Option<Picture>,
+	pub correct: Meaning,
+	/// The expected answer, as labelled on its choice button.
+	pub answer: String,
+	/// Every answer that counts as correct. Besides `answer` these are the
+	/// answers of other meanings that share the presented text, like
+	/// "Ser" and "Estar" for "Être".
+	pub accepted: Vec<String>,
+	/// Readings and romanizations of the accepted answers, which count as
+	/// correct when typed.
+	pub spellings: Vec<String>,
+	/// Words other regional variants use for the accepted answers, with
+	/// their language codes. Typing one is accepted as [`Verdict::Regional`].
+	pub regional: Vec<(String, String)>,
+	/// Shuffled answer choices for multiple choice, `answer` among them.
+	pub choices: Vec<String>,
+	/// Pictures to show on the choice buttons instead of their text, by
+	/// choice.
+	pictures: Vec<(String, Picture)>,
+	/// Scheduler key of the card being asked.
+	pub card: String,
+	/// Language of `presented_word`.
+	pub language_code: String,
+}
+
+impl Question {
+	fn new(
+		presented: String,
+		correct: Meaning,
+		answer: String,
+		accepted: Vec<String>,
+		mut choices: Vec<String>,
+		card: String,
+		lang: &str,
+	) -> Self {
+		choices.shuffle(&mut thread_rng());
+		Self {
+			presented_word: presented,
+			presented_reading: None,
+			presented_romanization: None,
+			presented_audio: None,
+			presented_picture: None,
+			correct,
+			answer,
+			accepted,
+			spellings: Vec::new(),
+			regional: Vec::new(),
+			choices,
+			pictures: Vec::new(),
+			card,
+			language_code: lang.to_string(),
+		}
+	}
+
+	/// The picture standing for `choice` when the choices are pictures.
+	pub fn choice_picture(&self, choice: &str) -> Option<&Picture> {
+		self.pictures.iter().find(|(c, _)| c == choice).map(|(_, p)| p)
+	}
+
+	/// The same question with its choices in a new order, so a retry cannot
+	/// be answered by remembering where the right button was.
+	fn reshuffled(mut self) -> Self {
+		self.choices.shuffle(&mut thread_rng());
+		self
+	}
+}
+
+/// What a session asks about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exercise {
+	/// Translate the presented word.
+	Translation,
+	/// Give the article of a noun in the learning language.
+	Gender,
+	/// Give one form of a verb in the learning language.
+	Conjugation,
+	/// Hear a word in the learning language and give it as written. Only
+	/// words with a recording are asked, unless a speech engine can say
+	/// the others.
+	Listening,
+	/// See a word in the learning language and pick its picture. Only
+	/// meanings with a picture are asked, always by multiple choice.
+	Pictures,
+}
+
+impl Exercise {
+	pub fn name(self) -> &'static str {
+		match self {
+			Exercise::Translation => "translation",
+			Exercise::Gender => "gender",
+			Exercise::Conjugation => "conjugation",
+			Exercise::Listening => "listening",
+			Exercise::Pictures => "pictures",
+		}
+	}
+}
+
+/// How the learner answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuizMode {
+	MultipleChoice,
+	Typed,
+}
+
+/// What happens to a question after a wrong answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryPolicy {
+	/// Ask it again straight away.
+	Now,
+	/// Ask it again after a few other questions.
+	Later,
+	/// Leave it to the scheduler.
+	MoveOn,
+}
+
+/// Questions asked in between before a [`RetryPolicy::Later`] retry.
+const RETRY_GAP: usize = 3;
+
+/// Points for a right answer, plus up to [`MAX_SPEED_BONUS`] more for
+/// answering a timed question quickly.
+pub const POINTS_PER_ANSWER: u32 = 10;
+pub const MAX_SPEED_BONUS: u32 = 10;
+
+/// Most choices a survival run grows to, one more for every
+/// [`STREAK_PER_CHOICE`] right answers in a row.
+pub const MAX_CHOICES: u32 = 7;
+const STREAK_PER_CHOICE: usize = 3;
+/// Right answers after which a survival run only offers the distractors
+/// that look most like the answer.
+const SIMILARITY_RAMP: u32 = 30;
+
+/// One thing that can be asked: a meaning, plus the verb form for
+/// conjugation drills.
+struct Item<'a> {
+	meaning: &'a Meaning,
+	form: Option<(Tense, Slot)>,
+}
+
+impl<'a> Item<'a> {
+	/// Everything in `vocab` that can be asked in `exercise`. Translation
+	/// needs both languages; the drills only need the learning language.
+	fn all(vocab: &'a [Meaning], exercise: Exercise, source: &str, lang: &str, speech: bool) -> Vec<Self> {
+		match exercise {
+			Exercise::Translation => vocab
+				.iter()
+				.filter(|m| m.word(source).is_some() && m.word(lang).is_some())
+				.map(|meaning| Item { meaning, form: None })
+				.collect(),
+			Exercise::Gender => vocab
+				.iter()
+				.filter(|m| m.word(lang).and_then(|w| w.drill_article(lang)).is_some())
+				.map(|meaning| Item { meaning, form: None })
+				.collect(),
+			Exercise::Listening => vocab
+				.iter()
+				.filter(|m| m.word(lang).is_some_and(|w| speech || w.audio.is_some()))
+				.map(|meaning| Item { meaning, form: None })
+				.collect(),
+			Exercise::Pictures => vocab
+				.iter()
+				.filter(|m| m.word(lang).is_some() && m.picture().is_some())
+				.map(|meaning| Item { meaning, form: None })
+				.collect(),
+			Exercise::Conjugation => vocab
+				.iter()
+				.filter_map(|m| Some((m, m.get_translation(lang)?)))
+				.filter(|(_, verb)| conjugation::conjugate(lang, verb, Tense::Present, conjugation::SLOTS[0]).is_some())
+				.flat_map(|(meaning, _)| conjugation::TENSES.into_iter().flat_map(move |tense| {
+					conjugation::SLOTS.into_iter().map(move |slot| Item { meaning, form: Some((tense, slot)) })
+				}))
+				.collect(),
+		}
+	}
+}
+
+/// Everything that shapes a session before its first question.
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+	/// Name of the deck, as recorded in the session history.
+	pub deck: String,
+	/// The language the learner knows.
+	pub source_lang: String,
+	/// The language being learned; prompts are shown in it.
+	pub target_lang: String,
+	/// Number of choices offered in multiple choice, the answer included.
+	pub num_choices: u32,
+	pub exercise: Exercise,
+	pub mode: QuizMode,
+	pub retry: RetryPolicy,
+	/// Answers after which the session ends; `None` runs until the learner
+	/// leaves.
+	pub length: Option<u32>,
+	/// Time allowed for each question; `None` for untimed sessions.
+	pub time_limit: Option<Duration>,
+	/// Wrong answers a survival run survives; the last one ends it. `None`
+	/// for sessions without lives.
+	pub lives: Option<u32>,
+	/// Whether the frontend can read words aloud, so that listening also
+	/// asks words without a recording.
+	pub speech: bool,
+}
+
+impl Default for SessionConfig {
+	fn default() -> Self {
+		Self {
+			deck: String::new(),
+			source_lang: "en".to_string(),
+			target_lang: "es".to_string(),
+			num_choices: 7,
+			exercise: Exercise::Translation,
+			mode: QuizMode::MultipleChoice,
+			retry: RetryPolicy::Now,
+			length: None,
+			time_limit: None,
+			lives: None,
+			speech: false,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Score {
+	pub correct: u32,
+	pub wrong: u32,
+	/// [`POINTS_PER_ANSWER`] per right answer, with speed bonuses in timed
+	/// sessions.
+	pub points: u32,
+}
+
+/// How a session went, for the page shown when it ends.
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+	pub answered: u32,
+	pub correct: u32,
+	/// Points scored, for timed sessions and survival runs.
+	pub points: Option<u32>,
+	/// Mean time from a question being shown to its answer.
+	pub average_time: Option<Duration>,
+	/// Most right answers in a row.
+	pub longest_streak: u32,
+	/// Every question answered wrongly at least once, in the order they
+	/// were first missed.
+	pub missed: Vec<Question>,
+}
+
+impl Summary {
+	/// Share of right answers, from 0 to 1, or `None` before any answer.
+	pub fn accuracy(&self) -> Option<f64> {
+		(self.answered > 0).then(|| f64::from(self.correct) / f64::from(self.answered))
+	}
+}
+
+/// One graded answer of the running session.
+struct Answer {
+	question: Question,
+	correct: bool,
+	time: Option<Duration>,
+}
+
+/// A running quiz session over one vocabulary. Call [`Game::next_question`],
+/// answer it with [`Game::check_answer`] or [`Game::check_typed`], repeat.
+/// Every answer is recorded in the learner's [`Progress`].
+pub struct Game {
+	deck: String,
+	vocab: Vec<Meaning>,
+	source_lang: String,
+	target_lang: String,
+	current: Option<Question>,
+	score: Score,
+	num_choices: u32,
+	exercise: Exercise,
+	mode: QuizMode,
+	retry: RetryPolicy,
+	length: Option<u32>,
+	time_limit: Option<Duration>,
+	lives: Option<u32>,
+	speech: bool,
+	/// Whether the current question was answered right, once it has been.
+	answered: Option<bool>,
+	/// Missed questions waiting to be asked again, each with the number of
+	/// questions that has to be reached before it comes back.
+	retries: VecDeque<(usize, Question)>,
+	asked: usize,
+	/// When the current question was put up.
+	shown_at: Option<Instant>,
+	answers: Vec<Answer>,
+	/// Cards a review session is limited to; empty for a normal session.
+	focus: Vec<String>,
+	progress: Progress,
+}
+
+impl Game {
+	pub fn new(config: SessionConfig, vocab: Vec<Meaning>, mut progress: Progress) -> Self {
+		progress.start_session(&config.deck, &config.source_lang, &config.target_lang, config.num_choices);
+		Self::build(config, vocab, progress)
+	}
+
+	/// A game with nothing to ask that only holds `progress` until the
+	/// first session is set up. It opens no session record.
+	pub fn idle(progress: Progress) -> Self {
+		Self::build(SessionConfig::default(), Vec::new(), progress)
+	}
+
+	fn build(config: SessionConfig, vocab: Vec<Meaning>, progress: Progress) -> Self {
+		Self {
+			deck: config.deck,
+			vocab,
+			source_lang: config.source_lang,
+			target_lang: config.target_lang,
+			current: None,
+			score: Score::default(),
+			num_choices: config.num_choices,
+			exercise: config.exercise,
+			// Pictures cannot be typed.
+			mode: if config.exercise == Exercise::Pictures { QuizMode::MultipleChoice } else { config.mode },
+			retry: config.retry,
+			length: config.length,
+			time_limit: config.time_limit,
+			lives: config.lives,
+			speech: config.speech,
+			answered: None,
+			retries: VecDeque::new(),
+			asked: 0,
+			shown_at: None,
+			answers: Vec::new(),
+			focus: Vec::new(),
+			progress,
+		}
+	}
+
+	pub fn current(&self) -> Option<&Question> {
+		self.current.as_ref()
+	}
+
+	pub fn score(&self) -> Score {
+		self.score
+	}
+
+	/// Whether the session has had as many answers as it was set up for,
+	/// a survival run has lost its last life, or, when reviewing, every card
+	/// under review has been answered right.
+	pub fn is_finished(&self) -> bool {
+		self.length.is_some_and(|n| self.score.correct + self.score.wrong >= n)
+			|| self.lives_left() == Some(0)
+			|| (!self.focus.is_empty() && self.focus.iter().all(|card| self.passed(card)))
+	}
+
+	fn passed(&self, card: &str) -> bool {
+		self.answers.iter().any(|a| a.correct && a.question.card == card)
+	}
+
+	pub fn summary(&self) -> Summary {
+		let mut summary = Summary {
+			answered: self.answers.len() as u32,
+			correct: self.answers.iter().filter(|a| a.correct).count() as u32,
+			points: (self.time_limit.is_some() || self.lives.is_some()).then_some(self.score.points),
+			..Summary::default()
+		};
+		let times: Vec<Duration> = self.answers.iter().filter_map(|a| a.time).collect();
+		if !times.is_empty() {
+			summary.average_time = Some(times.iter().sum::<Duration>() / times.len() as u32);
+		}
+		let mut streak = 0;
+		for answer in &self.answers {
+			streak = if answer.correct { streak + 1 } else { 0 };
+			summary.longest_streak = summary.longest_streak.max(streak);
+			if !answer.correct && !summary.missed.iter().any(|q| q.card == answer.question.card) {
+				summary.missed.push(answer.question.clone());
+			}
+		}
+		summary
+	}
+
+	/// Starts a session over the same vocabulary that asks only the
+	/// questions missed in this one, until each has been answered right.
+	/// Reviews are untimed and without lives. The learner's progress moves over to it. `None`
+	/// when nothing was missed.
+	pub fn review_missed(&mut self) -> Option<Game> {
+		let missed: Vec<String> = self.summary().missed.into_iter().map(|q| q.card).collect();
+		if missed.is_empty() {
+			return None;
+		}
+		let config = SessionConfig {
+			deck: self.deck.clone(),
+			source_lang: self.source_lang.clone(),
+			target_lang: self.target_lang.clone(),
+			num_choices: self.num_choices,
+			exercise: self.exercise,
+			mode: self.mode,
+			retry: self.retry,
+			length: None,
+			time_limit: None,
+			lives: None,
+			speech: self.speech,
+		};
+		let mut game = Game::new(config, self.vocab.clone(), self.take_progress());
+		game.focus = missed;
+		Some(game)
+	}
+
+	pub fn deck(&self) -> &str {
+		&self.deck
+	}
+
+	/// The number of choices the session was set up with.
+	pub fn num_choices(&self) -> u32 {
+		self.num_choices
+	}
+
+	/// The number of choices offered for the next question. Survival runs
+	/// add one to [`Game::num_choices`] for every few right answers in a
+	/// row, up to [`MAX_CHOICES`].
+	pub fn choices_offered(&self) -> u32 {
+		if self.lives.is_none() {
+			return self.num_choices;
+		}
+		let streak = self.answers.iter().rev().take_while(|a| a.correct).count();
+		let extra = (streak / STREAK_PER_CHOICE) as u32;
+		(self.num_choices + extra).min(MAX_CHOICES.max(self.num_choices))
+	}
+
+	/// Lives a survival run started with, `None` outside survival.
+	pub fn lives(&self) -> Option<u32> {
+		self.lives
+	}
+
+	pub fn lives_left(&self) -> Option<u32> {
+		self.lives.map(|n| n.saturating_sub(self.score.wrong))
+	}
+
+	pub fn exercise(&self) -> Exercise {
+		self.exercise
+	}
+
+	pub fn time_limit(&self) -> Option<Duration> {
+		self.time_limit
+	}
+
+	/// Time left to answer the current question of a timed session. Zero
+	/// once it has run out; `None` when untimed or already answered.
+	pub fn time_left(&self) -> Option<Duration> {
+		let limit = self.time_limit?;
+		if self.current.is_none() || self.answered.is_some() {
+			return None;
+		}
+		Some(limit.saturating_sub(self.shown_at?.elapsed()))
+	}
+
+	/// The current prompt and its language, for reading it aloud. Grammar
+	/// drills show a pattern rather than a word, so they have none.
+	pub fn spoken_prompt(&self) -> Option<(&str, &str)> {
+		let q = self.current.as_ref()?;
+		match self.exercise {
+			Exercise::Translation | Exercise::Listening | Exercise::Pictures => {
+				Some((&q.presented_word, &q.language_code))
+			}
+			Exercise::Gender | Exercise::Conjugation => None,
+		}
+	}
+
+	/// Counts the current question as missed because its time ran out.
+	pub fn time_out(&mut self) {
+		if self.answered.is_some() {
+			return;
+		}
+		if let Some(q) = &self.current {
+			self.progress.record_answer(&q.card, "", Grade::Again);
+			self.record(false);
+		}
+	}
+
+	pub fn mode(&self) -> QuizMode {
+		self.mode
+	}
+
+	pub fn source_lang(&self) -> &str {
+		&self.source_lang
+	}
+
+	pub fn target_lang(&self) -> &str {
+		&self.target_lang
+	}
+
+	pub fn progress(&self) -> &Progress {
+		&self.progress
+	}
+
+	/// Hands the learner's progress over to the next session.
+	pub fn take_progress(&mut self) -> Progress {
+		std::mem::take(&mut self.progress)
+	}
+
+	/// Gives back progress taken with [`Game::take_progress`], such as
+	/// after a matching board, without opening a session record.
+	pub fn replace_progress(&mut self, progress: Progress) {
+		self.progress = progress;
+	}
+
+	fn card_key(&self, item: &Item) -> String {
+		let m = item.meaning;
+		let target_text = m.get_translation(&self.target_lang).unwrap_or_default();
+		match (self.exercise, item.form) {
+			(Exercise::Gender, _) => format!("{}:{}|gender", self.target_lang, target_text),
+			(Exercise::Listening, _) => format!("{}:{}|listening", self.target_lang, target_text),
+			(Exercise::Pictures, _) => format!("{}:{}|picture", self.target_lang, target_text),
+			(Exercise::Conjugation, Some((tense, slot))) => format!(
+				"{}:{}|{} {}",
+				self.target_lang,
+				target_text,
+				conjugation::tense_name(&self.target_lang, tense),
+				conjugation::pronoun(&self.target_lang, slot),
+			),
+			_ => scheduler::card_key(
+				&self.source_lang,
+				&m.get_translation(&self.source_lang).unwrap_or_default(),
+				&self.target_lang,
+				&target_text,
+			),
+		}
+	}
+
+	/// Moves on to the next question and returns it, or `None` when the
+	/// session is finished or nothing in the vocabulary can be asked in this
+	/// exercise. A question answered wrongly comes back as the session's
+	/// [`RetryPolicy`] says.
+	pub fn next_question(&mut self) -> Option<&Question> {
+		if self.is_finished() {
+			self.current = None;
+			return None;
+		}
+		self.shown_at = Some(Instant::now());
+		let distractors = self.choices_offered().saturating_sub(1) as usize;
+		if self.answered.take() == Some(false)
+			&& let Some(missed) = self.current.take()
+		{
+			match self.retry {
+				RetryPolicy::Now => {
+					self.current = Some(missed.reshuffled());
+					return self.current.as_ref();
+				}
+				RetryPolicy::Later => self.retries.push_back((self.asked + RETRY_GAP, missed)),
+				RetryPolicy::MoveOn => {}
+			}
+		}
+		self.asked += 1;
+		if self.retries.front().is_some_and(|(due, _)| *due < self.asked)
+			&& let Some((_, retry)) = self.retries.pop_front()
+		{
+			self.current = Some(retry.reshuffled());
+			return self.current.as_ref();
+		}
+
+		let mut rng = thread_rng();
+		let items = Item::all(&self.vocab, self.exercise, &self.source_lang, &self.target_lang, self.speech);
+		if items.is_empty() {
+			self.current = None;
+			return None;
+		}
+		let keys: Vec<String> = items.iter().map(|item| self.card_key(item)).collect();
+		// Cards waiting for a retry are due today, but must not be asked
+		// again before their turn. A review only asks what is left of it.
+		let mut open: Vec<usize> = (0..keys.len())
+			.filter(|&i| !self.retries.iter().any(|(_, q)| q.card == keys[i]))
+			.filter(|&i| self.focus.is_empty() || (self.focus.contains(&keys[i]) && !self.passed(&keys[i])))
+			.collect();
+		if open.is_empty() {
+			open = (0..keys.len()).collect();
+		}
+		let open_keys: Vec<String> = open.iter().map(|&i| keys[i].clone()).collect();
+		let idx = self.progress.scheduler
+			.pick(&open_keys, scheduler::today())
+			.map(|i| open[i])
+			.unwrap_or_else(|| rng.gen_range(0..items.len()));
+		let item = &items[idx];
+		let correct = item.meaning.clone();
+		let card = keys[idx].clone();
+		let lang = &self.target_lang;
+
+		let question = match (self.exercise, item.form) {
+			(Exercise::Gender, _) => {
+				let word = correct.word(lang).unwrap();
+				let answer = word.drill_article(lang).unwrap().to_string();
+				let presented = format!("___ {}", word.lemma);
+				let choices = grammar::definite_articles(lang)
+					.iter()
+					.map(|(a, _)| a.to_string())
+					.collect();
+				Question::new(presented, correct, answer.clone(), vec![answer], choices, card, lang)
+			}
+			(Exercise::Listening, _) => {
+				let word = correct.word(lang).cloned().unwrap();
+				// Words written the same sound the same, so none of them is
+				// offered as a distractor.
+				let mut others: Vec<String> = items
+					.iter()
+					.filter_map(|item| item.meaning.get_translation(lang))
+					.filter(|text| *text != word.text)
+					.collect();
+				others.sort();
+				others.dedup();
+				let others = self.distractors(others, &word.text, distractors);
+				let answer = word.text.clone();
+				let mut choices = vec![answer.clone()];
+				choices.extend(others);
+				let presented = answer.clone();
+				let mut question = Question::new(presented, correct, answer.clone(), vec![answer], choices, card, lang);
+				question.spellings = word.spellings().map(str::to_string).collect();
+				question.presented_audio = word.audio;
+				question
+			}
+			(Exercise::Pictures, _) => {
+				let word = correct.word(lang).cloned().unwrap();
+				let picture = correct.picture().unwrap();
+				// Choices are labelled with the word they picture. No two of
+				// them may look the same, or the answer could be drawn twice.
+				let mut pictures: Vec<(String, Picture)> = items
+					.iter()
+					.filter_map(|item| Some((item.meaning.get_translation(lang)?, item.meaning.picture()?)))
+					.filter(|(text, _)| *text != word.text)
+					.collect();
+				pictures.shuffle(&mut rng);
+				let mut shown = vec![picture.clone()];
+				pictures.retain(|(_, p)| {
+					let new = !shown.contains(p);
+					shown.push(p.clone());
+					new
+				});
+				pictures.sort_by(|a, b| a.0.cmp(&b.0));
+				pictures.dedup_by(|a, b| a.0 == b.0);
+				let others = pictures.iter().map(|(text, _)| text.clone()).collect();
+				let others = self.distractors(others, &word.text, distractors);
+				let answer = word.text.clone();
+				let mut choices = vec![answer.clone()];
+				choices.extend(others);
+				pictures.push((answer.clone(), picture));
+				pictures.retain(|(text, _)| choices.contains(text));
+				let presented = answer.clone();
+				let mut question = Question::new(presented, correct, answer.clone(), vec![answer], choices, card, lang);
+				question.pictures = pictures;
+				question.presented_reading = word.reading;
+				question.presented_romanization = word.romanization;
+				question.presented_audio = word.audio;
+				question
+			}
+			(Exercise::Conjugation, Some((tense, slot))) => {
+				let verb = correct.get_translation(lang).unwrap().to_lowercase();
+				let answer = conjugation::conjugate(lang, &verb, tense, slot).unwrap();
+				let presented = format!(
+					"{} — {}, {}",
+					verb,
+					conjugation::pronoun(lang, slot),
+					conjugation::tense_name(lang, tense),
+				);
+				// Other forms of the same verb make the best distractors.
+				let others: Vec<String> = conjugation::all_forms(lang, &verb)
+					.into_iter()
+					.filter(|f| *f != answer)
+					.collect();
+				let others = self.distractors(others, &answer, distractors);
+				let mut choices = vec![answer.clone()];
+				choices.extend(others);
+				Question::new(presented, correct, answer.clone(), vec![answer], choices, card, lang)
+			}
+			_ => {
+				let label = |m: &Meaning| m
+					.get_translation(&self.source_lang)
+					.unwrap_or_else(|| "???".to_string());
+				let answer = label(&correct);
+				let presented = correct
+					.get_translation(lang)
+					.unwrap_or_else(|| "???".to_string());
+
+				// Meanings that read the same in the prompt language are all
+				// right answers, so none of them may be offered as a distractor.
+				let mut accepted: Vec<String> = items
+					.iter()
+					.map(|item| item.meaning)
+					.filter(|m| m.get_translation(lang).as_deref() == Some(presented.as_str()))
+					.map(label)
+					.collect();
+				accepted.sort();
+				accepted.dedup();
+
+				let mut others: Vec<String> = items
+					.iter()
+					.map(|item| label(item.meaning))
+					.filter(|l| l != "???" && !accepted.contains(l))
+					.collect();
+				others.sort();
+				others.dedup();
+				let others = self.distractors(others, &answer, distractors);
+
+				let answers: Vec<&Meaning> = items
+					.iter()
+					.map(|item| item.meaning)
+					.filter(|m| m.word(&self.source_lang).is_some_and(|w| accepted.contains(&w.text)))
+					.collect();
+				let spellings = answers
+					.iter()
+					.filter_map(|m| m.word(&self.source_lang))
+					.flat_map(|w| w.spellings().map(str::to_string))
+					.collect();
+				let regional = answers
+					.iter()
+					.flat_map(|m| m.other_variants(&self.source_lang))
+					.filter(|(_, w)| !accepted.contains(&w.text))
+					.map(|(code, w)| (code.to_string(), w.text.clone()))
+					.collect();
+				let prompt = correct.word(lang).cloned();
+				let picture = correct.picture();
+
+				let mut choices = vec![answer.clone()];
+				choices.extend(others);
+				let mut question = Question::new(presented, correct, answer, accepted, choices, card, lang);
+				question.spellings = spellings;
+				question.regional = regional;
+				if let Some(prompt) = prompt {
+					question.presented_reading = prompt.reading;
+					question.presented_romanization = prompt.romanization;
+					question.presented_audio = prompt.audio;
+				}
+				question.presented_picture = picture;
+				question
+			}
+		};
+		self.current = Some(question);
+		self.current.as_ref()
+	}
+
+	/// Picks `n` of `others` to offer next to `answer`. Survival runs pick
+	/// among the ones that look most like the answer, from fewer of them
+	/// the longer the run goes on.
+	fn distractors(&self, mut others: Vec<String>, answer: &str, n: usize) -> Vec<String> {
+		let mut rng = thread_rng();
+		others.shuffle(&mut rng);
+		if self.lives.is_some() {
+			// The shuffle above decides between equally close words.
+			let mut scored: Vec<(f64, String)> = others
+				.into_iter()
+				.map(|o| (matcher::similarity(&o, answer), o))
+				.collect();
+			scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+			let ramp = f64::from(self.score.correct.min(SIMILARITY_RAMP)) / f64::from(SIMILARITY_RAMP);
+			let pool = ((scored.len() as f64 * (1.0 - ramp)).ceil() as usize).max(n);
+			others = scored.into_iter().take(pool).map(|(_, o)| o).collect();
+			others.shuffle(&mut rng);
+		}
+		others.truncate(n);
+		others
+	}
+
+	/// The language answers are given in: the known language when
+	/// translating, the learning language in the other exercises.
+	pub fn answer_lang(&self) -> &str {
+		match self.exercise {
+			Exercise::Translation => &self.source_lang,
+			Exercise::Gender | Exercise::Conjugation | Exercise::Listening | Exercise::Pictures => &self.target_lang,
+		}
+	}
+
+	/// Grades the choice at `choice_index` of the current question. An
+	/// index without a choice behind it is ignored, and so is a second
+	/// answer to the same question.
+	/// An answer given after the time limit counts as a timeout.
+	pub fn check_answer(&mut self, choice_index: usize) -> bool {
+		if self.answered.is_some() {
+			return false;
+		}
+		if self.time_left() == Some(Duration::ZERO) {
+			self.time_out();
+			return false;
+		}
+		if let Some(q) = &self.current
+			&& let Some(chosen) = q.choices.get(choice_index)
+		{
+			let result = q.accepted.contains(chosen);
+			let grade = if result { Grade::Good } else { Grade::Again };
+			self.progress.record_answer(&q.card, chosen, grade);
+			self.record(result);
+			result
+		} else {
+			false
+		}
+	}
+
+	/// Grades a typed answer to the current question, which only counts
+	/// the first time.
+	pub fn check_typed(&mut self, input: &str, tolerance: &Tolerance) -> Verdict {
+		if self.answered.is_some() {
+			return Verdict::Wrong;
+		}
+		if self.time_left() == Some(Duration::ZERO) {
+			self.time_out();
+			return Verdict::Wrong;
+		}
+		let Some(q) = &self.current else {
+			return Verdict::Wrong;
+		};
+		// An article has nothing to misspell: "la" for "le" is the other
+		// gender.
+		let tolerance = &match self.exercise {
+			Exercise::Gender => Tolerance { max_typos: 0, ..*tolerance },
+			_ => *tolerance,
+		};
+		let verdict = q.accepted
+			.iter()
+			.chain(&q.spellings)
+			.map(|a| matcher::grade(input, a, self.answer_lang(), tolerance))
+			.min_by_key(|v| match v {
+				Verdict::Correct => 0,
+				Verdict::NearMiss => 1,
+				Verdict::Regional | Verdict::Wrong => 2,
+			})
+			.unwrap_or(Verdict::Wrong);
+		// A typo that spells another word of the deck, or another form of
+		// the verb asked about, is that word, not a slip: "pato" is no near
+		// miss for "el gato", nor "habla" for "hablan".
+		let lang = self.answer_lang();
+		let spells = |other: &str| matcher::grade(input, other, lang, tolerance) == Verdict::Correct;
+		let verdict = if verdict == Verdict::NearMiss
+			&& (self.vocab.iter().filter_map(|m| m.word(lang)).any(|w| spells(&w.text))
+				|| (self.exercise == Exercise::Conjugation
+					&& q.correct.get_translation(lang).is_some_and(|verb| {
+						conjugation::all_forms(lang, &verb.to_lowercase()).iter().any(|f| spells(f))
+					})))
+		{
+			Verdict::Wrong
+		} else {
+			verdict
+		};
+		let verdict = if verdict == Verdict::Wrong
+			&& q.regional
+				.iter()
+				.any(|(code, word)| matcher::grade(input, word, code, tolerance).is_accepted())
+		{
+			Verdict::Regional
+		} else {
+			verdict
+		};
+		let grade = match verdict {
+			Verdict::Correct | Verdict::Regional => Grade::Good,
+			Verdict::NearMiss => Grade::Hard,
+			Verdict::Wrong => Grade::Again,
+		};
+		self.progress.record_answer(&q.card, input.trim(), grade);
+		self.record(verdict.is_accepted());
+		verdict
+	}
+
+	/// Counts an answer to the current question towards the session.
+	fn record(&mut self, correct: bool) {
+		let Some(question) = &self.current else { return };
+		if correct {
+			self.score.correct += 1;
+			self.score.points += POINTS_PER_ANSWER;
+			if let (Some(limit), Some(left)) = (self.time_limit, self.time_left()) {
+				let share = left.as_secs_f64() / limit.as_secs_f64();
+				self.score.points += (share * f64::from(MAX_SPEED_BONUS)).round() as u32;
+			}
+		} else {
+			self.score.wrong += 1;
+		}
+		self.answers.push(Answer {
+			question: question.clone(),
+			correct,
+			time: self.shown_at.map(|t| t.elapsed()),
+		});
+		self.answered = Some(correct);
+	}
+}
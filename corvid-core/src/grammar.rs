@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+pub enum Gender {
+	#[serde(rename = "m", alias = "masculine")]
+	Masculine,
+	#[serde(rename = "f", alias = "feminine")]
+	Feminine,
+	#[serde(rename = "n", alias = "neuter")]
+	Neuter,
+}
+
+impl Gender {
+	pub fn name(self) -> &'static str {
+		match self {
+			Gender::Masculine => "masculine",
+			Gender::Feminine => "feminine",
+			Gender::Neuter => "neuter",
+		}
+	}
+}
+
+/// Singular definite articles of the languages Corvid knows how to drill,
+/// in the order they are offered as choices.
+pub fn definite_articles(lang: &str) -> &'static [(&'static str, Gender)] {
+	match language::base(lang) {
+		"es" => &[("el", Gender::Masculine), ("la", Gender::Feminine)],
+		"fr" => &[("le", Gender::Masculine), ("la", Gender::Feminine)],
+		"de" => &[("der", Gender::Masculine), ("die", Gender::Feminine), ("das", Gender::Neuter)],
+		_ => &[],
+	}
+}
+
+/// Articles that hide the gender of the noun they introduce.
+fn elided_articles(lang: &str) -> &'static [&'static str] {
+	match language::base(lang) {
+		"fr" => &["l’", "l'"],
+		_ => &[],
+	}
+}
+
+fn plural_articles(lang: &str) -> &'static [&'static str] {
+	match language::base(lang) {
+		"es" => &["los", "las"],
+		"fr" => &["les"],
+		_ => &[],
+	}
+}
+
+/// Noun endings that give the gender away with next to no exceptions.
+fn gender_endings(lang: &str) -> &'static [(&'static str, Gender)] {
+	use Gender::*;
+	match language::base(lang) {
+		"es" => &[
+			("ción", Feminine), ("sión", Feminine), ("dad", Feminine), ("tad", Feminine),
+			("tud", Feminine), ("umbre", Feminine), ("ismo", Masculine),
+		],
+		"fr" => &[("tion", Feminine), ("sion", Feminine), ("isme", Masculine)],
+		"de" => &[
+			("chen", Neuter), ("lein", Neuter), ("ung", Feminine), ("heit", Feminine),
+			("keit", Feminine), ("schaft", Feminine), ("tion", Feminine), ("tät", Feminine),
+			("ismus", Masculine),
+		],
+		_ => &[],
+	}
+}
+
+/// The gender a noun's ending suggests, if it has a telling one.
+pub fn gender_from_ending(lang: &str, lemma: &str) -> Option<Gender> {
+	let lemma = lemma.to_lowercase();
+	gender_endings(lang)
+		.iter()
+		.find(|(ending, _)| lemma.ends_with(ending) && lemma.len() > ending.len())
+		.map(|(_, gender)| *gender)
+}
+
+/// One language's side of a [`Meaning`](crate::Meaning): the text shown
+/// in the quiz plus the noun structure behind it, when known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translation {
+	pub text: String,
+	pub lemma: String,
+	pub article: Option<String>,
+	pub gender: Option<Gender>,
+	/// How `text` is read, in a script learners already know for the
+	/// language, like kana for Japanese kanji. Shown above the text.
+	pub reading: Option<String>,
+	/// `text` in Latin letters, such as "odin" for "один".
+	pub romanization: Option<String>,
+	/// A recording of `text` being said.
+	pub audio: Option<PathBuf>,
+}
+
+impl Translation {
+	/// Splits a leading article off `text` and infers the gender from it.
+	/// Elided and plural articles leave the gender unknown.
+	pub fn parse(lang: &str, text: &str) -> Self {
+		// Lowercasing can change how long the text is ("ẞ" becomes "ß"), so
+		// the article is compared on the original text, one char at a time.
+		let split = |article: &str, needs_space: bool| {
+			let end = text.char_indices().nth(article.chars().count()).map_or(text.len(), |(i, _)| i);
+			let (head, rest) = text.split_at(end);
+			if head.to_lowercase() != article {
+				return None;
+			}
+			let rest = if needs_space { rest.strip_prefix(' ')? } else { rest };
+			Some((head.to_string(), rest.trim_start().to_string()))
+		};
+
+		for (article, gender) in definite_articles(lang) {
+			if let Some((article, lemma)) = split(article, true) {
+				return Self { article: Some(article), gender: Some(*gender), ..Self::plain(text, lemma) };
+			}
+		}
+		for article in elided_articles(lang) {
+			if let Some((article, lemma)) = split(article, false) {
+				return Self { article: Some(article), ..Self::plain(text, lemma) };
+			}
+		}
+		for article in plural_articles(lang) {
+			if let Some((article, lemma)) = split(article, true) {
+				return Self { article: Some(article), ..Self::plain(text, lemma) };
+			}
+		}
+		Self::plain(text, text.to_string())
+	}
+
+	fn plain(text: &str, lemma: String) -> Self {
+		Self {
+			text: text.to_string(),
+			lemma,
+			article: None,
+			gender: None,
+			reading: None,
+			romanization: None,
+			audio: None,
+		}
+	}
+
+	/// Other ways a learner may type this word: its reading and its
+	/// romanization.
+	pub fn spellings(&self) -> impl Iterator<Item = &str> {
+		self.reading.iter().chain(&self.romanization).map(String::as_str)
+	}
+
+	pub fn is_plural(&self, lang: &str) -> bool {
+		self.article
+			.as_deref()
+			.is_some_and(|a| plural_articles(lang).contains(&a.to_lowercase().as_str()))
+	}
+
+	/// The article a gender drill expects: the one written in the deck when
+	/// it is a singular definite article ("el agua" stays "el"), otherwise
+	/// the article matching the gender ("l’oiseau" asks for "le"). Plurals
+	/// and nouns of unknown gender cannot be drilled.
+	pub fn drill_article(&self, lang: &str) -> Option<&'static str> {
+		if self.is_plural(lang) {
+			return None;
+		}
+		let articles = definite_articles(lang);
+		let written = self.article.as_deref().map(str::to_lowercase);
+		if let Some((a, _)) = articles.iter().find(|(a, _)| Some(*a) == written.as_deref()) {
+			return Some(a);
+		}
+		let gender = self.gender?;
+		articles.iter().find(|(_, g)| *g == gender).map(|(a, _)| *a)
+	}
+}
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+use std::{io, path::Path};
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, QuizMode};
+use crate::language::Languages;
+use crate::persist::{self, LoadError};
+
+/// Entries kept in each table.
+pub const TABLE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+	/// Name of the profile that played.
+	pub name: String,
+	pub points: u32,
+	pub correct: u32,
+	pub answered: u32,
+	pub at: u64,
+}
+
+/// The best lightning rounds and survival runs on this installation, one
+/// table per topic, exercise, difficulty and language pair, shared by all
+/// profiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScores {
+	tables: BTreeMap<String, Vec<HighScore>>,
+}
+
+impl HighScores {
+	/// Reads `path`, starting with empty tables when it does not exist yet.
+	pub fn load(path: &Path) -> Result<Self, LoadError> {
+		Ok(persist::load_or_quarantine(path, |text| serde_json::from_str(text))?.unwrap_or_default())
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+		persist::save_atomic(path, &json)
+	}
+
+	/// Names the table `game` competes in. Rounds share a table only when
+	/// they drill the same topic and languages, answered the same way
+	/// against the same clock.
+	pub fn key_for(game: &Game) -> String {
+		format!(
+			"{}|{}|{}|{}>{}",
+			game.deck(),
+			game.exercise().name(),
+			difficulty(game),
+			game.target_lang(),
+			game.source_lang(),
+		)
+	}
+
+	/// The heading of the table `game` competes in.
+	pub fn title_for(game: &Game, languages: &Languages) -> String {
+		format!(
+			"High scores for {}, {}, {}, {} to {}",
+			game.deck(),
+			game.exercise().name(),
+			difficulty(game),
+			languages.name(game.target_lang()),
+			languages.name(game.source_lang()),
+		)
+	}
+
+	/// The table for `key`, best first.
+	pub fn table(&self, key: &str) -> &[HighScore] {
+		self.tables.get(key).map_or(&[], Vec::as_slice)
+	}
+
+	/// Enters a round into the table for `key` and returns its place,
+	/// counting from 0, or `None` when it did not make the table. Ties go
+	/// to the earlier round.
+	pub fn submit(&mut self, key: &str, score: HighScore) -> Option<usize> {
+		let table = self.tables.entry(key.to_string()).or_default();
+		let place = table.iter().position(|s| s.points < score.points).unwrap_or(table.len());
+		if place >= TABLE_SIZE {
+			return None;
+		}
+		table.insert(place, score);
+		table.truncate(TABLE_SIZE);
+		Some(place)
+	}
+}
+
+/// How a round was set up, in words. Survival runs offer more choices as
+/// they go on, so their number of choices is left out.
+fn difficulty(game: &Game) -> String {
+	let mut parts = Vec::new();
+	if game.lives().is_some() {
+		parts.push("survival".to_string());
+	}
+	match game.mode() {
+		QuizMode::Typed => parts.push("typed".to_string()),
+		QuizMode::MultipleChoice if game.lives().is_none() => parts.push(format!("{} choices", game.num_choices())),
+		QuizMode::MultipleChoice => {}
+	}
+	if let Some(limit) = game.time_limit() {
+		parts.push(format!("{} s per question", limit.as_secs_f64()));
+	}
+	parts.join(", ")
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use crate::deck::Deck;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	LeftToRight,
+	RightToLeft,
+}
+
+/// What Corvid knows about a language code found in a deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+	/// BCP-47 tag as written in the deck, such as "es" or "sr-Latn".
+	pub code: String,
+	pub name: String,
+	/// ISO 15924 script code, such as "Latn" or "Cyrl".
+	pub script: String,
+	pub direction: Direction,
+}
+
+/// Primary language subtags Corvid has a name for, with their usual script.
+const KNOWN: &[(&str, &str, &str)] = &[
+	("ar", "Arabic", "Arab"),
+	("ca", "Catalan", "Latn"),
+	("cs", "Czech", "Latn"),
+	("da", "Danish", "Latn"),
+	("de", "German", "Latn"),
+	("el", "Greek", "Grek"),
+	("en", "English", "Latn"),
+	("eo", "Esperanto", "Latn"),
+	("es", "Spanish", "Latn"),
+	("fa", "Persian", "Arab"),
+	("fi", "Finnish", "Latn"),
+	("fr", "French", "Latn"),
+	("he", "Hebrew", "Hebr"),
+	("hi", "Hindi", "Deva"),
+	("hu", "Hungarian", "Latn"),
+	("it", "Italian", "Latn"),
+	("ja", "Japanese", "Jpan"),
+	("ko", "Korean", "Kore"),
+	("la", "Latin", "Latn"),
+	("nl", "Dutch", "Latn"),
+	("no", "Norwegian", "Latn"),
+	("pl", "Polish", "Latn"),
+	("pt", "Portuguese", "Latn"),
+	("ro", "Romanian", "Latn"),
+	("ru", "Russian", "Cyrl"),
+	("sr", "Serbian", "Cyrl"),
+	("sv", "Swedish", "Latn"),
+	("tr", "Turkish", "Latn"),
+	("uk", "Ukrainian", "Cyrl"),
+	("zh", "Chinese", "Hans"),
+];
+
+/// Region subtags with a friendlier name than the code.
+const REGIONS: &[(&str, &str)] = &[
+	("419", "Latin America"),
+	("AR", "Argentina"),
+	("AT", "Austria"),
+	("BE", "Belgium"),
+	("BR", "Brazil"),
+	("CA", "Canada"),
+	("CH", "Switzerland"),
+	("DE", "Germany"),
+	("ES", "Spain"),
+	("FR", "France"),
+	("GB", "United Kingdom"),
+	("MX", "Mexico"),
+	("PT", "Portugal"),
+	("US", "United States"),
+];
+
+const RIGHT_TO_LEFT_SCRIPTS: &[&str] = &["Arab", "Hebr", "Syrc", "Thaa"];
+
+impl Language {
+	/// Describes `code` from its subtags: the primary language gives the
+	/// name and default script, a script subtag overrides the script and a
+	/// region subtag is added to the name. Unknown languages are named by
+	/// their code.
+	pub fn new(code: &str) -> Self {
+		let mut subtags = code.split(['-', '_']);
+		let primary = subtags.next().unwrap_or_default().to_lowercase();
+		let known = KNOWN.iter().find(|(c, _, _)| *c == primary);
+
+		let mut name = known.map_or_else(|| code.to_string(), |(_, name, _)| name.to_string());
+		let mut script = known.map_or("Latn", |(_, _, script)| *script).to_string();
+		for subtag in subtags {
+			if subtag.len() == 4 && subtag.chars().all(|c| c.is_ascii_alphabetic()) {
+				script = subtag[..1].to_uppercase() + &subtag[1..].to_lowercase();
+			} else if known.is_some()
+				&& (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_alphabetic())
+					|| subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+			{
+				let region = subtag.to_uppercase();
+				let region = REGIONS
+					.iter()
+					.find(|(code, _)| *code == region)
+					.map_or(region.as_str(), |(_, name)| name);
+				name = format!("{} ({})", name, region);
+			}
+		}
+
+		let direction = if RIGHT_TO_LEFT_SCRIPTS.contains(&script.as_str()) {
+			Direction::RightToLeft
+		} else {
+			Direction::LeftToRight
+		};
+		Self { code: code.to_string(), name, script, direction }
+	}
+}
+
+/// The code of the language called `name`, such as "es" for "Spanish".
+/// A known code is returned as it is; case does not matter.
+pub fn code_for_name(name: &str) -> Option<&'static str> {
+	let name = name.trim();
+	KNOWN
+		.iter()
+		.find(|(code, known, _)| known.eq_ignore_ascii_case(name) || code.eq_ignore_ascii_case(name))
+		.map(|(code, _, _)| *code)
+}
+
+/// Every language Corvid has a name for, sorted by name.
+pub fn known() -> Vec<Language> {
+	let mut all: Vec<Language> = KNOWN.iter().map(|(code, _, _)| Language::new(code)).collect();
+	all.sort_by(|a, b| a.name.cmp(&b.name));
+	all
+}
+
+/// The primary language subtag of `code`: "es" for "es-419". Grammar
+/// rules are shared by all regional variants of a language.
+pub fn base(code: &str) -> &str {
+	code.split(['-', '_']).next().unwrap_or(code)
+}
+
+/// Every language that appears in the loaded decks.
+#[derive(Debug, Clone, Default)]
+pub struct Languages {
+	by_code: BTreeMap<String, Language>,
+}
+
+impl Languages {
+	pub fn from_decks(decks: &[Deck]) -> Self {
+		let by_code = decks
+			.iter()
+			.flat_map(|d| d.languages())
+			.map(|code| (code.clone(), Language::new(&code)))
+			.collect();
+		Self { by_code }
+	}
+
+	/// Registers the languages of a deck loaded after the others.
+	pub fn add_deck(&mut self, deck: &Deck) {
+		for code in deck.languages() {
+			self.by_code.entry(code.clone()).or_insert_with(|| Language::new(&code));
+		}
+	}
+
+	pub fn get(&self, code: &str) -> Option<&Language> {
+		self.by_code.get(code)
+	}
+
+	/// Display name of `code`, or the code itself when it is not registered.
+	pub fn name(&self, code: &str) -> String {
+		self.get(code).map_or_else(|| code.to_string(), |l| l.name.clone())
+	}
+
+	pub fn direction(&self, code: &str) -> Direction {
+		self.get(code).map_or(Direction::LeftToRight, |l| l.direction)
+	}
+
+	/// All languages, sorted by display name.
+	pub fn all(&self) -> Vec<&Language> {
+		let mut all: Vec<&Language> = self.by_code.values().collect();
+		all.sort_by(|a, b| a.name.cmp(&b.name));
+		all
+	}
+}
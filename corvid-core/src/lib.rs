@@ -0,0 +1,126 @@
+//! The Corvid quiz engine: decks, questions, grading, scheduling and saved
+//! progress, with no user interface attached. A frontend loads decks,
+//! starts a [`Game`] and feeds it the learner's answers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub mod anki;
+pub mod conjugation;
+pub mod deck;
+mod game;
+pub mod grammar;
+pub mod highscores;
+pub mod language;
+pub mod matcher;
+pub mod matching;
+pub mod persist;
+pub mod profile;
+pub mod scheduler;
+pub mod settings;
+pub mod stats;
+pub mod storage;
+pub mod validate;
+
+pub use game::{Exercise, Game, Question, QuizMode, RetryPolicy, Score, SessionConfig, Summary};
+
+use grammar::Translation;
+
+/// One concept and its translations, keyed by language code.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Meaning {
+	translations: HashMap<String, Translation>,
+	note: Option<String>,
+	image: Option<PathBuf>,
+	emoji: Option<String>,
+}
+
+/// How a meaning is shown as a picture.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Picture {
+	Image(PathBuf),
+	/// For decks that ship no images, like "🐕" for "The dog".
+	Emoji(String),
+}
+
+impl Meaning {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add_translation(&mut self, code: &str, text: &str) {
+		self.add_word(code, Translation::parse(code, text));
+	}
+
+	pub fn add_word(&mut self, code: &str, word: Translation) {
+		self.translations.insert(code.to_string(), word);
+	}
+
+	pub fn get_translation(&self, code: &str) -> Option<String> {
+		self.word(code).map(|t| t.text.clone())
+	}
+
+	/// The translation in `code`. A regional code such as "es-419" falls
+	/// back to the base language when the meaning has no regional word.
+	pub fn word(&self, code: &str) -> Option<&Translation> {
+		self.translations
+			.get(code)
+			.or_else(|| self.translations.get(language::base(code)))
+	}
+
+	/// Words of the other regional variants of `code`'s language that differ
+	/// from the word in `code`, with their language codes.
+	pub fn other_variants(&self, code: &str) -> Vec<(&str, &Translation)> {
+		let own = self.word(code).map(|w| w.text.as_str());
+		let mut variants: Vec<(&str, &Translation)> = self.translations
+			.iter()
+			.filter(|(c, w)| language::base(c) == language::base(code) && Some(w.text.as_str()) != own)
+			.map(|(c, w)| (c.as_str(), w))
+			.collect();
+		variants.sort_by_key(|(c, _)| *c);
+		variants
+	}
+
+	/// Codes of the languages this meaning has a translation in.
+	pub fn languages(&self) -> impl Iterator<Item = &str> {
+		self.translations.keys().map(String::as_str)
+	}
+
+	/// An explanation shown after the meaning is asked, such as when to
+	/// use "ser" rather than "estar".
+	pub fn note(&self) -> Option<&str> {
+		self.note.as_deref()
+	}
+
+	pub fn set_note(&mut self, note: &str) {
+		self.note = Some(note.to_string());
+	}
+
+	/// The image file given in the deck, whether or not it is there.
+	pub fn image(&self) -> Option<&Path> {
+		self.image.as_deref()
+	}
+
+	pub fn set_image(&mut self, path: PathBuf) {
+		self.image = Some(path);
+	}
+
+	pub fn emoji(&self) -> Option<&str> {
+		self.emoji.as_deref()
+	}
+
+	pub fn set_emoji(&mut self, emoji: &str) {
+		self.emoji = Some(emoji.to_string());
+	}
+
+	/// The picture to show for this meaning: its image, or its emoji when
+	/// it has no image or the image file is missing.
+	pub fn picture(&self) -> Option<Picture> {
+		if let Some(image) = &self.image
+			&& (image.is_file() || self.emoji.is_none())
+		{
+			return Some(Picture::Image(image.clone()));
+		}
+		self.emoji.clone().map(Picture::Emoji)
+	}
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::language;
+
+/// How forgiving typed-answer grading is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tolerance {
+	/// "raton" counts as "ratón".
+	pub ignore_diacritics: bool,
+	/// "perro" counts as "El perro", and "el perro" as "Perro". A typed
+	/// article still has to be right.
+	pub optional_articles: bool,
+	/// Edit distance that is still accepted as a near miss.
+	pub max_typos: usize,
+}
+
+impl Default for Tolerance {
+	fn default() -> Self {
+		Self {
+			ignore_diacritics: true,
+			optional_articles: true,
+			max_typos: 1,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+	Correct,
+	/// Accepted, but with a typo.
+	NearMiss,
+	/// Accepted, but it is the word of another regional variant, such as
+	/// "la patata" when learning Latin American Spanish.
+	Regional,
+	Wrong,
+}
+
+impl Verdict {
+	pub fn is_accepted(self) -> bool {
+		self != Verdict::Wrong
+	}
+}
+
+/// Leading articles per language. Elided forms end in an apostrophe and
+/// attach directly to the noun.
+fn articles(lang: &str) -> &'static [&'static str] {
+	match language::base(lang) {
+		"en" => &["the", "a", "an", "to"],
+		"es" => &["el", "la", "los", "las", "un", "una"],
+		"fr" => &["le", "la", "les", "un", "une", "l'"],
+		"de" => &["der", "die", "das", "ein", "eine"],
+		_ => &[],
+	}
+}
+
+/// Lowercases, trims, collapses whitespace and maps typographic
+/// apostrophes to `'`, optionally folding away diacritics.
+pub fn normalize(text: &str, ignore_diacritics: bool) -> String {
+	let text: String = text
+		.chars()
+		.map(|c| match c {
+			'’' | '‘' | 'ʼ' | '`' | '´' => '\'',
+			c => c,
+		})
+		.collect();
+	let text = text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+	if ignore_diacritics {
+		text.nfd().filter(|c| !is_combining_mark(*c)).nfc().collect()
+	} else {
+		text.nfc().collect()
+	}
+}
+
+/// Splits a normalized phrase into its leading article, if any, and the rest.
+fn split_article<'a>(text: &'a str, lang: &str) -> (Option<&'a str>, &'a str) {
+	for article in articles(lang) {
+		if article.ends_with('\'') {
+			if let Some(rest) = text.strip_prefix(article) {
+				return (Some(article), rest.trim_start());
+			}
+		} else if let Some(rest) = text.strip_prefix(article).and_then(|r| r.strip_prefix(' ')) {
+			return (Some(article), rest);
+		}
+	}
+	(None, text)
+}
+
+pub fn grade(input: &str, expected: &str, lang: &str, tol: &Tolerance) -> Verdict {
+	let input = normalize(input, tol.ignore_diacritics);
+	let expected = normalize(expected, tol.ignore_diacritics);
+	if input.is_empty() {
+		return Verdict::Wrong;
+	}
+
+	// Typos are forgiven in the word only: "la chat" is the wrong gender,
+	// not a slip of the keyboard.
+	let (typed_article, typed_word) = split_article(&input, lang);
+	let (article, word) = split_article(&expected, lang);
+	let (input, expected) = match (typed_article, article) {
+		(Some(typed), Some(article)) if typed != article => return Verdict::Wrong,
+		(Some(_), Some(_)) => (typed_word, word),
+		(None, Some(_)) | (Some(_), None) if tol.optional_articles => (typed_word, word),
+		_ => (input.as_str(), expected.as_str()),
+	};
+
+	match levenshtein(input, expected) {
+		0 => Verdict::Correct,
+		d if d <= tol.max_typos => Verdict::NearMiss,
+		_ => Verdict::Wrong,
+	}
+}
+
+/// How alike two answers look, from 0 for nothing in common to 1 for the
+/// same text once normalized.
+pub fn similarity(a: &str, b: &str) -> f64 {
+	let a = normalize(a, true);
+	let b = normalize(b, true);
+	let longest = a.chars().count().max(b.chars().count());
+	if longest == 0 {
+		return 1.0;
+	}
+	1.0 - levenshtein(&a, &b) as f64 / longest as f64
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	let mut prev: Vec<usize> = (0..=b.len()).collect();
+	let mut cur = vec![0; b.len() + 1];
+	for (i, ca) in a.iter().enumerate() {
+		cur[0] = i + 1;
+		for (j, cb) in b.iter().enumerate() {
+			let cost = if ca == cb { 0 } else { 1 };
+			cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+		}
+		std::mem::swap(&mut prev, &mut cur);
+	}
+	prev[b.len()]
+}
@@ -0,0 +1,219 @@
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::Meaning;
+use crate::matcher;
+use crate::scheduler::{self, Grade};
+use crate::storage::Progress;
+
+/// Pairs on a board unless the frontend asks for another number.
+pub const DEFAULT_PAIRS: usize = 6;
+
+/// Which column a tile belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+	/// The language the learner knows.
+	Source,
+	/// The language being learned.
+	Target,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tile {
+	pub text: String,
+	pub side: Side,
+	/// Index of the pair the tile belongs to.
+	pair: usize,
+	pub matched: bool,
+}
+
+/// What picking a tile did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+	/// The tile is now selected, waiting for its partner.
+	Selected,
+	/// The selected tile was picked again and let go.
+	Released,
+	/// The two tiles at these indices belong together.
+	Matched(usize, usize),
+	/// The two tiles at these indices do not belong together.
+	Mismatched(usize, usize),
+	/// The tile was already matched.
+	Ignored,
+}
+
+/// A matching-pairs board: a column of words in the known language and a
+/// column of their translations, each shuffled, to be paired up.
+///
+/// No two pairs on a board share a word on the same side, so every tile
+/// has exactly one partner. Pairing the tiles records answers in the
+/// learner's [`Progress`] like a [`crate::Game`] does.
+pub struct Board {
+	source_lang: String,
+	target_lang: String,
+	/// Scheduler keys of the pairs, by pair index.
+	cards: Vec<String>,
+	tiles: Vec<Tile>,
+	/// Index of the tile picked first, waiting for its partner.
+	selected: Option<usize>,
+	/// Pairs that were part of a mismatch before being matched.
+	missed: Vec<usize>,
+	mismatches: u32,
+	started: Instant,
+	finished: Option<Duration>,
+	progress: Progress,
+}
+
+impl Board {
+	/// Deals up to `pairs` pairs from `vocab`, due cards first. The board is
+	/// empty when not even two pairs can be dealt without two words looking
+	/// the same.
+	pub fn new(
+		deck: &str,
+		vocab: &[Meaning],
+		source_lang: &str,
+		target_lang: &str,
+		pairs: usize,
+		mut progress: Progress,
+	) -> Self {
+		let mut candidates: Vec<(String, String, String)> = vocab
+			.iter()
+			.filter_map(|m| {
+				let source = m.get_translation(source_lang)?;
+				let target = m.get_translation(target_lang)?;
+				let key = scheduler::card_key(source_lang, &source, target_lang, &target);
+				Some((key, source, target))
+			})
+			.collect();
+
+		progress.start_session(deck, source_lang, target_lang, pairs as u32);
+		let same = |a: &str, b: &str| matcher::normalize(a, false) == matcher::normalize(b, false);
+		let mut dealt: Vec<(String, String, String)> = Vec::new();
+		while dealt.len() < pairs {
+			let keys: Vec<String> = candidates.iter().map(|(key, _, _)| key.clone()).collect();
+			let Some(i) = progress.scheduler.pick(&keys, scheduler::today()) else { break };
+			let pair = candidates.swap_remove(i);
+			candidates.retain(|(_, source, target)| !same(source, &pair.1) && !same(target, &pair.2));
+			dealt.push(pair);
+		}
+		if dealt.len() < 2 {
+			dealt.clear();
+		}
+
+		let mut rng = thread_rng();
+		let mut sources: Vec<Tile> = dealt
+			.iter()
+			.enumerate()
+			.map(|(pair, (_, text, _))| Tile { text: text.clone(), side: Side::Source, pair, matched: false })
+			.collect();
+		let mut targets: Vec<Tile> = dealt
+			.iter()
+			.enumerate()
+			.map(|(pair, (_, _, text))| Tile { text: text.clone(), side: Side::Target, pair, matched: false })
+			.collect();
+		sources.shuffle(&mut rng);
+		targets.shuffle(&mut rng);
+		sources.extend(targets);
+
+		Self {
+			source_lang: source_lang.to_string(),
+			target_lang: target_lang.to_string(),
+			cards: dealt.into_iter().map(|(key, _, _)| key).collect(),
+			tiles: sources,
+			selected: None,
+			missed: Vec::new(),
+			mismatches: 0,
+			started: Instant::now(),
+			finished: None,
+			progress,
+		}
+	}
+
+	/// The source tiles, then the target tiles.
+	pub fn tiles(&self) -> &[Tile] {
+		&self.tiles
+	}
+
+	pub fn pairs(&self) -> usize {
+		self.cards.len()
+	}
+
+	pub fn selected(&self) -> Option<usize> {
+		self.selected
+	}
+
+	pub fn mismatches(&self) -> u32 {
+		self.mismatches
+	}
+
+	pub fn source_lang(&self) -> &str {
+		&self.source_lang
+	}
+
+	pub fn target_lang(&self) -> &str {
+		&self.target_lang
+	}
+
+	/// Time since the board was dealt, stopped once it is cleared.
+	pub fn elapsed(&self) -> Duration {
+		self.finished.unwrap_or_else(|| self.started.elapsed())
+	}
+
+	/// Whether every pair has been matched. An empty board never is.
+	pub fn is_complete(&self) -> bool {
+		self.finished.is_some()
+	}
+
+	/// Picks the tile at `index`. The first pick selects it; picking another
+	/// tile on the same side moves the selection there, and a tile on the
+	/// other side is checked against it. A mismatch counts against the
+	/// word picked first.
+	pub fn pick(&mut self, index: usize) -> Pick {
+		let Some(tile) = self.tiles.get(index) else { return Pick::Ignored };
+		if tile.matched {
+			return Pick::Ignored;
+		}
+		let first = match self.selected {
+			Some(first) if first == index => {
+				self.selected = None;
+				return Pick::Released;
+			}
+			Some(first) if self.tiles[first].side != tile.side => first,
+			_ => {
+				self.selected = Some(index);
+				return Pick::Selected;
+			}
+		};
+		self.selected = None;
+
+		let (prompt, chosen) = (&self.tiles[first], &self.tiles[index]);
+		let card = &self.cards[prompt.pair];
+		if prompt.pair == chosen.pair {
+			let pair = prompt.pair;
+			let grade = if self.missed.contains(&pair) { Grade::Hard } else { Grade::Good };
+			self.progress.record_answer(card, &chosen.text, grade);
+			self.tiles[first].matched = true;
+			self.tiles[index].matched = true;
+			if self.tiles.iter().all(|t| t.matched) {
+				self.finished = Some(self.started.elapsed());
+			}
+			Pick::Matched(first, index)
+		} else {
+			self.progress.record_answer(card, &chosen.text, Grade::Again);
+			self.missed.push(prompt.pair);
+			self.mismatches += 1;
+			Pick::Mismatched(first, index)
+		}
+	}
+
+	pub fn progress(&self) -> &Progress {
+		&self.progress
+	}
+
+	/// Hands the learner's progress over to the next session.
+	pub fn take_progress(&mut self) -> Progress {
+		std::mem::take(&mut self.progress)
+	}
+}
@@ -0,0 +1,117 @@
+//! Reading and writing the files Corvid keeps its state in. A file that
+//! cannot be parsed is moved aside, so the next save does not overwrite
+//! whatever the learner might still recover from it.
+
+use std::ffi::OsString;
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::storage::now_secs;
+
+/// Why a state file was not used. Whoever loaded it carries on with the
+/// defaults and decides how to tell the learner.
+#[derive(Debug)]
+pub enum LoadError {
+	/// The file exists but cannot be read.
+	Io(PathBuf, io::Error),
+	/// The file cannot be parsed. It was renamed to `backup`, or left in
+	/// place if that failed.
+	Unparsable {
+		path: PathBuf,
+		error: String,
+		backup: Option<PathBuf>,
+	},
+}
+
+impl Display for LoadError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			LoadError::Io(path, e) => write!(f, "cannot read {}: {}", path.display(), e),
+			LoadError::Unparsable { path, error, backup: Some(backup) } => {
+				write!(f, "{} is unreadable ({}), moved it to {}", path.display(), error, backup.display())
+			}
+			LoadError::Unparsable { path, error, backup: None } => {
+				write!(f, "{} is unreadable ({}) and cannot be moved aside", path.display(), error)
+			}
+		}
+	}
+}
+
+impl std::error::Error for LoadError {}
+
+/// Reads `path` and parses it with `parse`. Returns `Ok(None)` when the
+/// file does not exist, and renames it to `<file>.broken-<seconds>` when
+/// `parse` rejects it.
+pub(crate) fn load_or_quarantine<T, E: Display>(
+	path: &Path,
+	parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<Option<T>, LoadError> {
+	let text = match fs::read_to_string(path) {
+		Ok(text) => text,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(LoadError::Io(path.to_path_buf(), e)),
+	};
+	parse(&text).map(Some).map_err(|e| {
+		let backup = with_suffix(path, &format!(".broken-{}", now_secs()));
+		LoadError::Unparsable {
+			path: path.to_path_buf(),
+			error: e.to_string(),
+			backup: fs::rename(path, &backup).is_ok().then_some(backup),
+		}
+	})
+}
+
+/// A step that brings a state file from one version of its layout to the
+/// next.
+pub(crate) type Migration = fn(&mut Value);
+
+/// Loads a state file that records the `version` of its layout, taking a
+/// file without one for version 1. `parse` turns the text into a value of
+/// the file's own format; older layouts then go through `migrations`, the
+/// first of which turns version 1 into version 2. A file from a newer
+/// Corvid is moved aside like an unparsable one, since saving over it
+/// would lose whatever this version does not know about.
+pub(crate) fn load_versioned<T: DeserializeOwned>(
+	path: &Path,
+	parse: impl FnOnce(&str) -> Result<Value, String>,
+	migrations: &[Migration],
+) -> Result<Option<T>, LoadError> {
+	let current = migrations.len() as u64 + 1;
+	load_or_quarantine(path, |text| {
+		let mut value = parse(text)?;
+		let version = match value.get("version") {
+			None => 1,
+			Some(version) => version.as_u64().filter(|&v| v > 0).ok_or("its version is not a version number")?,
+		};
+		if version > current {
+			return Err(format!("it was written by a newer Corvid, in version {} of its layout", version));
+		}
+		for migrate in &migrations[version as usize - 1..] {
+			migrate(&mut value);
+		}
+		if let Some(fields) = value.as_object_mut() {
+			fields.insert("version".to_string(), current.into());
+		}
+		serde_json::from_value(value).map_err(|e| e.to_string())
+	})
+}
+
+/// Writes `text` to `path` through a temporary file, so a crash never
+/// leaves a half-written file behind.
+pub(crate) fn save_atomic(path: &Path, text: &str) -> io::Result<()> {
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir)?;
+	}
+	let tmp = with_suffix(path, ".tmp");
+	fs::write(&tmp, text)?;
+	fs::rename(&tmp, path)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+	let mut name = OsString::from(path.as_os_str());
+	name.push(suffix);
+	PathBuf::from(name)
+}
@@ -0,0 +1,160 @@
+use std::{fmt, io, path::Path};
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{self, LoadError};
+
+/// One learner. The id names the learner's files and never changes; the
+/// name is what the menus show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+	pub id: String,
+	pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+	EmptyName,
+	NameTaken(String),
+	NotFound(String),
+	LastProfile,
+}
+
+impl fmt::Display for ProfileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ProfileError::EmptyName => write!(f, "a profile needs a name"),
+			ProfileError::NameTaken(name) => write!(f, "there is already a profile called {}", name),
+			ProfileError::NotFound(id) => write!(f, "no profile with id {}", id),
+			ProfileError::LastProfile => write!(f, "the last profile cannot be deleted"),
+		}
+	}
+}
+
+impl std::error::Error for ProfileError {}
+
+/// The learners sharing an installation and which of them is playing.
+///
+/// ```toml
+/// active = "default"
+///
+/// [[profile]]
+/// id = "default"
+/// name = "Default"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profiles {
+	active: String,
+	#[serde(rename = "profile")]
+	profiles: Vec<Profile>,
+}
+
+impl Default for Profiles {
+	fn default() -> Self {
+		Self {
+			active: "default".to_string(),
+			profiles: vec![Profile { id: "default".to_string(), name: "Default".to_string() }],
+		}
+	}
+}
+
+impl Profiles {
+	/// Reads `path`, falling back to a single default profile when it does
+	/// not exist or lists nobody.
+	pub fn load(path: &Path) -> Result<Self, LoadError> {
+		Ok(match persist::load_or_quarantine(path, |text| toml::from_str::<Self>(text))? {
+			Some(profiles) if profiles.profiles.is_empty() => Self::default(),
+			Some(mut profiles) => {
+				if profiles.get(&profiles.active).is_none() {
+					profiles.active = profiles.profiles[0].id.clone();
+				}
+				profiles
+			}
+			None => Self::default(),
+		})
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let text = toml::to_string_pretty(self).map_err(io::Error::other)?;
+		persist::save_atomic(path, &text)
+	}
+
+	/// Every profile, in the order they were created.
+	pub fn all(&self) -> &[Profile] {
+		&self.profiles
+	}
+
+	pub fn get(&self, id: &str) -> Option<&Profile> {
+		self.profiles.iter().find(|p| p.id == id)
+	}
+
+	pub fn active(&self) -> &Profile {
+		self.get(&self.active).unwrap_or(&self.profiles[0])
+	}
+
+	pub fn set_active(&mut self, id: &str) -> Result<(), ProfileError> {
+		self.get(id).ok_or_else(|| ProfileError::NotFound(id.to_string()))?;
+		self.active = id.to_string();
+		Ok(())
+	}
+
+	/// Adds a learner called `name` and returns the new profile's id, made
+	/// from the name so the learner's files are easy to find.
+	pub fn create(&mut self, name: &str) -> Result<String, ProfileError> {
+		let name = self.check_name(name, None)?;
+		let stem: String = name
+			.to_lowercase()
+			.chars()
+			.map(|c| if c.is_alphanumeric() { c } else { '-' })
+			.collect();
+		let stem = stem.trim_matches('-');
+		let stem = if stem.is_empty() { "profile" } else { stem };
+		let id = (1..)
+			.map(|n| if n == 1 { stem.to_string() } else { format!("{}-{}", stem, n) })
+			.find(|id| self.get(id).is_none())
+			.expect("some suffix is free");
+		self.profiles.push(Profile { id: id.clone(), name });
+		Ok(id)
+	}
+
+	pub fn rename(&mut self, id: &str, name: &str) -> Result<(), ProfileError> {
+		let name = self.check_name(name, Some(id))?;
+		let profile = self.profiles
+			.iter_mut()
+			.find(|p| p.id == id)
+			.ok_or_else(|| ProfileError::NotFound(id.to_string()))?;
+		profile.name = name;
+		Ok(())
+	}
+
+	/// Removes a profile. Deleting the active one makes the first remaining
+	/// profile active. The learner's files are left to the caller.
+	pub fn remove(&mut self, id: &str) -> Result<Profile, ProfileError> {
+		let index = self.profiles
+			.iter()
+			.position(|p| p.id == id)
+			.ok_or_else(|| ProfileError::NotFound(id.to_string()))?;
+		if self.profiles.len() == 1 {
+			return Err(ProfileError::LastProfile);
+		}
+		let removed = self.profiles.remove(index);
+		if self.active == removed.id {
+			self.active = self.profiles[0].id.clone();
+		}
+		Ok(removed)
+	}
+
+	/// Trims `name` and checks that no other profile than `except` uses it.
+	fn check_name(&self, name: &str, except: Option<&str>) -> Result<String, ProfileError> {
+		let name = name.trim();
+		if name.is_empty() {
+			return Err(ProfileError::EmptyName);
+		}
+		let taken = self.profiles
+			.iter()
+			.any(|p| Some(p.id.as_str()) != except && p.name.to_lowercase() == name.to_lowercase());
+		if taken {
+			return Err(ProfileError::NameTaken(name.to_string()));
+		}
+		Ok(name.to_string())
+	}
+}
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, time::{SystemTime, UNIX_EPOCH}};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use serde::{Deserialize, Serialize};
+
+/// Days since the Unix epoch.
+pub type Day = i64;
+
+pub fn today() -> Day {
+	let secs = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or(0);
+	(secs / 86_400) as Day
+}
+
+/// How well a card was remembered, mapped onto the SM-2 quality scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+	Again,
+	Hard,
+	Good,
+}
+
+impl Grade {
+	fn quality(self) -> f32 {
+		match self {
+			Grade::Again => 1.0,
+			Grade::Hard => 3.0,
+			Grade::Good => 4.0,
+		}
+	}
+}
+
+const INITIAL_EASE: f32 = 2.5;
+const MIN_EASE: f32 = 1.3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardState {
+	pub due: Day,
+	pub interval: u32,
+	pub ease: f32,
+	pub reps: u32,
+	pub lapses: u32,
+}
+
+impl CardState {
+	fn new(today: Day) -> Self {
+		Self {
+			due: today,
+			interval: 0,
+			ease: INITIAL_EASE,
+			reps: 0,
+			lapses: 0,
+		}
+	}
+
+	/// SM-2 update. A failed card is due again the same day so it comes
+	/// back later in the session.
+	fn review(&mut self, grade: Grade, today: Day) {
+		let q = grade.quality();
+		if q < 3.0 {
+			self.reps = 0;
+			self.interval = 0;
+			self.lapses += 1;
+		} else {
+			self.reps += 1;
+			self.interval = match self.reps {
+				1 => 1,
+				2 => 6,
+				_ => (self.interval as f32 * self.ease).round() as u32,
+			};
+		}
+		self.ease = (self.ease + 0.1 - (5.0 - q) * (0.08 + (5.0 - q) * 0.02)).max(MIN_EASE);
+		self.due = today + self.interval as Day;
+	}
+}
+
+/// Tracks a `CardState` per card key and decides which card to ask next.
+/// Keys are built with [`card_key`], so the same word studied in two
+/// language pairs is scheduled independently.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scheduler {
+	cards: HashMap<String, CardState>,
+	#[serde(skip)]
+	new_per_session: usize,
+	#[serde(skip)]
+	new_this_session: usize,
+	#[serde(skip)]
+	last: Option<String>,
+}
+
+impl Scheduler {
+	pub fn new(new_per_session: usize) -> Self {
+		Self {
+			cards: HashMap::new(),
+			new_per_session,
+			new_this_session: 0,
+			last: None,
+		}
+	}
+
+	pub fn start_session(&mut self) {
+		self.new_this_session = 0;
+		self.last = None;
+	}
+
+	/// Picks the index into `keys` to ask next: the most overdue card first,
+	/// then an unseen card while the new-card limit allows, then whichever
+	/// seen card is due soonest. The previous card is avoided when possible.
+	pub fn pick(&mut self, keys: &[String], today: Day) -> Option<usize> {
+		let mut rng = thread_rng();
+		let mut candidates: Vec<usize> = (0..keys.len())
+			.filter(|&i| self.last.as_deref() != Some(keys[i].as_str()))
+			.collect();
+		if candidates.is_empty() {
+			candidates = (0..keys.len()).collect();
+		}
+		candidates.shuffle(&mut rng);
+
+		let due = candidates
+			.iter()
+			.filter_map(|&i| self.cards.get(&keys[i]).map(|c| (i, c.due)))
+			.filter(|&(_, d)| d <= today)
+			.min_by_key(|&(_, d)| d)
+			.map(|(i, _)| i);
+
+		let unseen = || candidates
+			.iter()
+			.copied()
+			.find(|&i| !self.cards.contains_key(&keys[i]));
+
+		let picked = due
+			.or_else(|| {
+				if self.new_this_session < self.new_per_session { unseen() } else { None }
+			})
+			.or_else(|| candidates
+				.iter()
+				.filter_map(|&i| self.cards.get(&keys[i]).map(|c| (i, c.due)))
+				.min_by_key(|&(_, d)| d)
+				.map(|(i, _)| i))
+			.or_else(unseen)?;
+
+		if !self.cards.contains_key(&keys[picked]) {
+			self.new_this_session += 1;
+		}
+		self.last = Some(keys[picked].clone());
+		Some(picked)
+	}
+
+	/// Every card seen so far with its scheduling state.
+	pub fn states(&self) -> impl Iterator<Item = (&str, &CardState)> {
+		self.cards.iter().map(|(key, state)| (key.as_str(), state))
+	}
+
+	pub fn state(&self, key: &str) -> Option<&CardState> {
+		self.cards.get(key)
+	}
+
+	pub fn record(&mut self, key: &str, grade: Grade, today: Day) {
+		self.cards
+			.entry(key.to_string())
+			.or_insert_with(|| CardState::new(today))
+			.review(grade, today);
+	}
+}
+
+impl Default for Scheduler {
+	fn default() -> Self {
+		Self::new(NEW_CARDS_PER_SESSION)
+	}
+}
+
+pub const NEW_CARDS_PER_SESSION: usize = 10;
+
+/// Identifies one direction of one meaning: the prompt shown in `target`
+/// and the answer expected in `source`.
+pub fn card_key(source: &str, source_text: &str, target: &str, target_text: &str) -> String {
+	format!("{}:{}|{}:{}", target, target_text, source, source_text)
+}
@@ -0,0 +1,169 @@
+use std::{collections::BTreeMap, io, path::Path, time::Duration};
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Exercise, QuizMode, RetryPolicy, SessionConfig};
+use crate::language;
+use crate::matcher::Tolerance;
+use crate::persist::{self, LoadError, Migration};
+
+/// The layout of [`Settings`] files, bumped with every migration step.
+pub const SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Steps that bring settings files from one layout to the next, oldest
+/// first. None of the layouts has been replaced yet.
+const MIGRATIONS: &[Migration] = &[];
+
+/// The kind of round a session is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Challenge {
+	/// Untimed practice.
+	Practice,
+	/// [`LIGHTNING_QUESTIONS`] questions, each against the clock, for a
+	/// place in the high scores.
+	Lightning,
+	/// Play until [`SURVIVAL_LIVES`] wrong answers, with more and closer
+	/// choices as the streak grows. The longest runs are kept.
+	Survival,
+}
+
+/// Length of a lightning round. It is fixed so that high scores compare.
+pub const LIGHTNING_QUESTIONS: u32 = 20;
+/// Lives of a survival run.
+pub const SURVIVAL_LIVES: u32 = 3;
+/// Choices a survival run starts with, whatever the difficulty.
+pub const SURVIVAL_CHOICES: u32 = 3;
+
+/// How a speech engine reads one language.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Voice {
+	/// The engine's voice, such as "de+f3" for espeak-ng; `None` lets the
+	/// engine pick one for the language.
+	pub name: Option<String>,
+	/// Speed relative to the engine's normal rate.
+	pub rate: f32,
+}
+
+impl Default for Voice {
+	fn default() -> Self {
+		Self { name: None, rate: 1.0 }
+	}
+}
+
+/// The learner's preferences, kept between launches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+	pub version: u32,
+	pub source_lang: String,
+	pub target_lang: String,
+	/// Ask in the known language and answer in the learning one.
+	pub reverse: bool,
+	pub exercise: Exercise,
+	pub mode: QuizMode,
+	pub retry: RetryPolicy,
+	pub num_choices: u32,
+	pub tolerance: Tolerance,
+	pub show_romanization: bool,
+	/// Name of the deck to practise without picking a topic first.
+	pub default_deck: Option<String>,
+	/// Answers per session; `None` for sessions without an end.
+	pub session_length: Option<u32>,
+	pub challenge: Challenge,
+	/// Seconds allowed per question in lightning rounds.
+	pub time_limit: u32,
+	/// Read each prompt aloud when it is shown.
+	pub speak_prompts: bool,
+	/// Speech settings by language code.
+	///
+	/// ```toml
+	/// [voices.de]
+	/// name = "de+m3"
+	/// rate = 0.8
+	/// ```
+	pub voices: BTreeMap<String, Voice>,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			version: SETTINGS_VERSION,
+			source_lang: "en".to_string(),
+			target_lang: "es".to_string(),
+			reverse: false,
+			exercise: Exercise::Translation,
+			mode: QuizMode::MultipleChoice,
+			retry: RetryPolicy::Now,
+			num_choices: 5,
+			tolerance: Tolerance::default(),
+			show_romanization: true,
+			default_deck: None,
+			session_length: None,
+			challenge: Challenge::Practice,
+			time_limit: 5,
+			speak_prompts: false,
+			voices: BTreeMap::new(),
+		}
+	}
+}
+
+impl Settings {
+	/// Reads `path`, or gives the defaults when it does not exist yet.
+	pub fn load(path: &Path) -> Result<Self, LoadError> {
+		let parse = |text: &str| {
+			let table: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+			serde_json::to_value(table).map_err(|e| e.to_string())
+		};
+		Ok(persist::load_versioned(path, parse, MIGRATIONS)?.unwrap_or_default())
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let text = toml::to_string_pretty(self).map_err(io::Error::other)?;
+		persist::save_atomic(path, &text)
+	}
+
+	/// The voice for `lang`, falling back to the one for its base language
+	/// and then to the engine's defaults.
+	pub fn voice(&self, lang: &str) -> Voice {
+		self.voices
+			.get(lang)
+			.or_else(|| self.voices.get(language::base(lang)))
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	/// The session these settings describe for `deck`. Grammar drills always
+	/// ask about the learning language, so they ignore `reverse`. Lightning
+	/// rounds have their own length, survival runs their own difficulty.
+	pub fn session_config(&self, deck: &str) -> SessionConfig {
+		let (source_lang, target_lang) = if self.reverse && self.exercise == Exercise::Translation {
+			(self.target_lang.clone(), self.source_lang.clone())
+		} else {
+			(self.source_lang.clone(), self.target_lang.clone())
+		};
+		SessionConfig {
+			deck: deck.to_string(),
+			source_lang,
+			target_lang,
+			num_choices: match self.challenge {
+				Challenge::Survival => SURVIVAL_CHOICES,
+				_ => self.num_choices,
+			},
+			exercise: self.exercise,
+			mode: self.mode,
+			retry: self.retry,
+			length: match self.challenge {
+				Challenge::Practice => self.session_length,
+				Challenge::Lightning => Some(LIGHTNING_QUESTIONS),
+				Challenge::Survival => None,
+			},
+			time_limit: match self.challenge {
+				Challenge::Lightning => Some(Duration::from_secs(self.time_limit.max(1).into())),
+				_ => None,
+			},
+			lives: (self.challenge == Challenge::Survival).then_some(SURVIVAL_LIVES),
+			speech: false,
+		}
+	}
+}
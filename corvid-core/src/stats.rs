@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use crate::Meaning;
+use crate::scheduler::{CardState, Day};
+use crate::storage::Progress;
+
+/// Answers given on one day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayCount {
+	pub day: Day,
+	pub reviews: u32,
+	pub correct: u32,
+}
+
+impl DayCount {
+	pub fn accuracy(&self) -> f64 {
+		f64::from(self.correct) / f64::from(self.reviews.max(1))
+	}
+}
+
+/// Answers per day, oldest first, for the days anything was answered.
+pub fn daily(progress: &Progress) -> Vec<DayCount> {
+	let mut days: BTreeMap<Day, DayCount> = BTreeMap::new();
+	for answer in &progress.answers {
+		let day = (answer.at / 86_400) as Day;
+		let count = days.entry(day).or_insert(DayCount { day, reviews: 0, correct: 0 });
+		count.reviews += 1;
+		if answer.correct {
+			count.correct += 1;
+		}
+	}
+	days.into_values().collect()
+}
+
+/// How well a word is known, judged by how far apart the scheduler spaces
+/// its reviews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Mastery {
+	/// Never answered.
+	New,
+	/// Reviewed again within a few days.
+	Learning,
+	/// Reviewed every one to three weeks.
+	Familiar,
+	/// Reviewed three weeks apart or more.
+	Mastered,
+}
+
+impl Mastery {
+	pub const ALL: [Mastery; 4] = [Mastery::New, Mastery::Learning, Mastery::Familiar, Mastery::Mastered];
+
+	pub fn of(state: &CardState) -> Self {
+		match state.interval {
+			0..6 => Mastery::Learning,
+			6..21 => Mastery::Familiar,
+			_ => Mastery::Mastered,
+		}
+	}
+
+	pub fn name(self) -> &'static str {
+		match self {
+			Mastery::New => "New",
+			Mastery::Learning => "Learning",
+			Mastery::Familiar => "Familiar",
+			Mastery::Mastered => "Mastered",
+		}
+	}
+}
+
+/// Number of `meanings` at each [`Mastery`] level, in the order of
+/// [`Mastery::ALL`]. A meaning counts at the level of its best known card,
+/// whatever the language pair or exercise it was practised in.
+pub fn mastery(progress: &Progress, meanings: &[Meaning]) -> [u32; 4] {
+	// Card keys start with the prompt, "lang:text|", so a meaning's cards
+	// are found by its translations.
+	let mut best: BTreeMap<&str, Mastery> = BTreeMap::new();
+	for (key, state) in progress.scheduler.states() {
+		let prompt = key.split('|').next().unwrap_or(key);
+		let level = Mastery::of(state);
+		let entry = best.entry(prompt).or_insert(level);
+		*entry = (*entry).max(level);
+	}
+
+	let mut counts = [0; 4];
+	for meaning in meanings {
+		let level = meaning
+			.languages()
+			.filter_map(|code| {
+				let text = meaning.get_translation(code)?;
+				best.get(format!("{}:{}", code, text).as_str()).copied()
+			})
+			.max()
+			.unwrap_or(Mastery::New);
+		counts[level as usize] += 1;
+	}
+	counts
+}
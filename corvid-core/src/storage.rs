@@ -0,0 +1,145 @@
+use std::{collections::HashMap, io, path::Path, time::{SystemTime, UNIX_EPOCH}};
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{self, LoadError, Migration};
+use crate::scheduler::{Day, Grade, Scheduler};
+
+/// The layout of progress files, bumped with every migration step.
+pub const FORMAT_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// Steps that bring progress files from one layout to the next, oldest
+/// first. None of the layouts has been replaced yet.
+const MIGRATIONS: &[Migration] = &[];
+
+pub fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CardStats {
+	pub seen: u32,
+	pub correct: u32,
+	pub wrong: u32,
+	pub last_seen: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerRecord {
+	pub session: u32,
+	pub at: u64,
+	pub card: String,
+	pub chosen: String,
+	pub correct: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+	pub id: u32,
+	pub deck: String,
+	pub source_lang: String,
+	pub target_lang: String,
+	pub num_choices: u32,
+	pub started: u64,
+	pub ended: u64,
+	pub correct: u32,
+	pub wrong: u32,
+}
+
+/// Everything Corvid remembers about a learner between launches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Progress {
+	pub version: u32,
+	pub scheduler: Scheduler,
+	pub cards: HashMap<String, CardStats>,
+	pub answers: Vec<AnswerRecord>,
+	pub sessions: Vec<SessionRecord>,
+}
+
+impl Default for Progress {
+	fn default() -> Self {
+		Self {
+			version: FORMAT_VERSION,
+			scheduler: Scheduler::default(),
+			cards: HashMap::new(),
+			answers: Vec::new(),
+			sessions: Vec::new(),
+		}
+	}
+}
+
+impl Progress {
+	/// Reads `path`, starting fresh when it does not exist yet.
+	pub fn load(path: &Path) -> Result<Self, LoadError> {
+		let parse = |text: &str| serde_json::from_str(text).map_err(|e| e.to_string());
+		Ok(persist::load_versioned(path, parse, MIGRATIONS)?.unwrap_or_default())
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+		persist::save_atomic(path, &json)
+	}
+
+	/// Opens a new session record. A previous session that never got an
+	/// answer is replaced instead of being kept as an empty entry.
+	pub fn start_session(&mut self, deck: &str, source_lang: &str, target_lang: &str, num_choices: u32) {
+		if self.sessions.last().is_some_and(|s| s.correct + s.wrong == 0) {
+			self.sessions.pop();
+		}
+		let now = now_secs();
+		let id = self.sessions.last().map_or(1, |s| s.id + 1);
+		self.sessions.push(SessionRecord {
+			id,
+			deck: deck.to_string(),
+			source_lang: source_lang.to_string(),
+			target_lang: target_lang.to_string(),
+			num_choices,
+			started: now,
+			ended: now,
+			correct: 0,
+			wrong: 0,
+		});
+		self.scheduler.start_session();
+	}
+
+	pub fn record_answer(&mut self, card: &str, chosen: &str, grade: Grade) {
+		let correct = grade != Grade::Again;
+		let now = now_secs();
+		let session = match self.sessions.last_mut() {
+			Some(s) => {
+				s.ended = now;
+				if correct { s.correct += 1 } else { s.wrong += 1 }
+				s.id
+			}
+			None => 0,
+		};
+		self.log(session, now, card, chosen, grade);
+	}
+
+	/// Records a review made outside Corvid at `at`, such as one imported
+	/// from Anki. It belongs to no session. Reviews of a card must be
+	/// imported oldest first.
+	pub fn import_review(&mut self, card: &str, grade: Grade, at: u64) {
+		self.log(0, at, card, "", grade);
+	}
+
+	fn log(&mut self, session: u32, at: u64, card: &str, chosen: &str, grade: Grade) {
+		let correct = grade != Grade::Again;
+		let stats = self.cards.entry(card.to_string()).or_default();
+		stats.seen += 1;
+		if correct { stats.correct += 1 } else { stats.wrong += 1 }
+		stats.last_seen = stats.last_seen.max(at);
+
+		self.answers.push(AnswerRecord {
+			session,
+			at,
+			card: card.to_string(),
+			chosen: chosen.to_string(),
+			correct,
+		});
+
+		self.scheduler.record(card, grade, (at / 86_400) as Day);
+	}
+}
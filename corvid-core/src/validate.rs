@@ -0,0 +1,168 @@
+use std::{collections::{BTreeSet, HashMap}, fs, path::Path};
+
+use crate::deck::{Deck, DeckError};
+use crate::grammar::{self, Gender, Translation};
+use crate::language;
+use crate::matcher;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+/// A problem found in a deck file, at a 1-based line.
+#[derive(Debug)]
+pub struct Issue {
+	pub severity: Severity,
+	pub line: usize,
+	pub message: String,
+}
+
+/// Checks one deck file for duplicate meanings, missing languages, texts
+/// shared by several meanings, articles that disagree with the gender, and
+/// audio clips and images that are not there.
+pub fn check_file(path: &Path) -> Result<Vec<Issue>, DeckError> {
+	let text = fs::read_to_string(path).map_err(|e| DeckError::Io(path.to_path_buf(), e))?;
+	let deck = Deck::parse(&text, path)?;
+	Ok(check(&deck, &text))
+}
+
+fn check(deck: &Deck, text: &str) -> Vec<Issue> {
+	let lines = Lines::new(text);
+	let mut issues = Vec::new();
+	let mut issue = |severity, line, message| issues.push(Issue { severity, line, message });
+
+	let langs: BTreeSet<&str> = deck.meanings
+		.iter()
+		.flat_map(|m| m.translations.keys().map(String::as_str))
+		.collect();
+
+	let mut seen: HashMap<(&str, String), usize> = HashMap::new();
+	for (i, meaning) in deck.meanings.iter().enumerate() {
+		let line = lines.meaning(i);
+
+		if let Some(first) = deck.meanings[..i].iter().position(|m| m == meaning) {
+			issue(Severity::Error, line, format!("duplicate of the meaning at line {}", lines.meaning(first)));
+			continue;
+		}
+
+		let missing: Vec<&str> = langs
+			.iter()
+			.copied()
+			.filter(|lang| meaning.word(lang).is_none())
+			.collect();
+		if !missing.is_empty() {
+			issue(Severity::Error, line, format!("missing {}", missing.join(", ")));
+		}
+		if let Some(image) = meaning.image()
+			&& !image.is_file()
+		{
+			// The emoji is shown instead, so the meaning still has a picture.
+			let severity = if meaning.emoji().is_some() { Severity::Warning } else { Severity::Error };
+			issue(severity, lines.key(i, "image"), format!("image {} does not exist", image.display()));
+		}
+
+		let mut codes: Vec<&String> = meaning.translations.keys().collect();
+		codes.sort();
+		for lang in codes {
+			let word = &meaning.translations[lang];
+			let line = lines.key(i, lang);
+
+			let shown = matcher::normalize(&word.text, false);
+			match seen.get(&(lang.as_str(), shown.clone())) {
+				Some(&first) => issue(
+					Severity::Warning,
+					line,
+					format!("{} “{}” is also used by the meaning at line {}", lang, word.text, lines.meaning(first)),
+				),
+				None => {
+					seen.insert((lang.as_str(), shown), i);
+				}
+			}
+
+			if let Some(message) = article_mismatch(lang, word) {
+				issue(Severity::Error, line, message);
+			}
+			if let Some(audio) = &word.audio
+				&& !audio.is_file()
+			{
+				issue(Severity::Error, line, format!("{} audio clip {} does not exist", lang, audio.display()));
+			}
+			if let (Some(gender), Some(expected)) = (word.gender, grammar::gender_from_ending(lang, &word.lemma))
+				&& gender != expected
+			{
+				issue(
+					Severity::Warning,
+					line,
+					format!("{} “{}” is {}, but nouns ending like “{}” are usually {}",
+						lang, word.text, gender.name(), word.lemma, expected.name()),
+				);
+			}
+		}
+	}
+	issues
+}
+
+/// Compares the gender given in the deck with the one its written article
+/// implies. Feminine nouns starting with a stressed "a" take "el" in
+/// Spanish ("el agua"), which is not a mismatch.
+fn article_mismatch(lang: &str, word: &Translation) -> Option<String> {
+	let written = Translation::parse(lang, &word.text);
+	let (Some(implied), Some(given)) = (written.gender, word.gender) else {
+		return None;
+	};
+	if implied == given {
+		return None;
+	}
+	let lemma = word.lemma.to_lowercase();
+	if language::base(lang) == "es"
+		&& given == Gender::Feminine
+		&& ["a", "á", "ha", "há"].iter().any(|p| lemma.starts_with(p))
+	{
+		return None;
+	}
+	Some(format!(
+		"{} “{}” is marked {}, but “{}” is {}",
+		lang, word.text, given.name(), written.article.unwrap_or_default(), implied.name(),
+	))
+}
+
+/// Line numbers of the `[[meaning]]` tables in a deck file, so issues can
+/// point at the source even though the parsed deck no longer knows them.
+struct Lines<'a> {
+	text: Vec<&'a str>,
+	headers: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+	fn new(text: &'a str) -> Self {
+		let text: Vec<&str> = text.lines().collect();
+		let headers = text
+			.iter()
+			.enumerate()
+			.filter(|(_, l)| l.trim() == "[[meaning]]")
+			.map(|(i, _)| i)
+			.collect();
+		Self { text, headers }
+	}
+
+	/// 1-based line of the `i`th meaning's header.
+	fn meaning(&self, i: usize) -> usize {
+		self.headers.get(i).map_or(0, |&l| l + 1)
+	}
+
+	/// 1-based line of `lang` inside the `i`th meaning, or its header.
+	fn key(&self, i: usize, lang: &str) -> usize {
+		let Some(&start) = self.headers.get(i) else { return 0 };
+		let end = self.headers.get(i + 1).copied().unwrap_or(self.text.len());
+		(start + 1..end)
+			.find(|&l| {
+				self.text[l]
+					.trim_start()
+					.strip_prefix(lang)
+					.is_some_and(|rest| rest.trim_start().starts_with('='))
+			})
+			.map_or(start + 1, |l| l + 1)
+	}
+}
@@ -0,0 +1,218 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use corvid_core::anki::{Export, Field, ImportError};
+use corvid_core::deck::Deck;
+use corvid_core::scheduler::{self, Grade};
+use corvid_core::storage::Progress;
+use corvid_core::Picture;
+use rusqlite::Connection;
+use ruzstd::encoding::{CompressionLevel, compress_to_vec};
+use zip::write::{SimpleFileOptions, ZipWriter};
+
+#[test]
+fn text_exports_become_decks() {
+	let dir = std::env::temp_dir().join(format!("corvid-anki-text-{}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	let export = dir.join("export.txt");
+	fs::write(
+		&export,
+		"#separator:tab\n#html:true\n#columns:English\tSpanish\tPicture\tNotes\n#deck column:5\n\
+		The dog\tEl&nbsp;perro\t🐕\t\tLanguages::Animals\n\
+		The cat\t<b>El gato</b>[sound:gato.mp3]\t\t\"Also \"\"minino\"\",\nwhen small\"\tLanguages::Animals\n\
+		The dog\tEl perro\t🐕\t\tLanguages::Animals\n\
+		\t\t\tonly a note\tLanguages::Animals\n",
+	)
+	.unwrap();
+
+	let export = Export::read(&export).unwrap();
+	assert_eq!(export.name, "Animals");
+	assert_eq!(export.fields, ["English", "Spanish", "Picture", "Notes", "Deck"]);
+	let fields = export.guess_fields();
+	assert_eq!(
+		fields,
+		[Field::Language("en".to_string()), Field::Language("es".to_string()), Field::Picture, Field::Note, Field::Skip],
+	);
+
+	let decks = dir.join("decks");
+	fs::create_dir_all(&decks).unwrap();
+	fs::write(decks.join("animals.toml"), "name = \"Mine\"\n").unwrap();
+	let imported = export.import(&fields, &decks).unwrap();
+	// The duplicate and the note without a word are left out, and the
+	// existing deck keeps its file.
+	assert_eq!(imported.skipped, 2);
+	assert_eq!(imported.missing_media, 1);
+	assert_eq!(imported.deck.path, decks.join("animals_2.toml"));
+
+	let deck = Deck::load(&imported.deck.path).unwrap();
+	assert_eq!(deck.name, "Animals");
+	assert_eq!(deck.meanings, imported.deck.meanings);
+	assert_eq!(deck.meanings.len(), 2);
+	assert_eq!(deck.meanings[0].get_translation("es").as_deref(), Some("El perro"));
+	assert_eq!(deck.meanings[0].picture(), Some(Picture::Emoji("🐕".to_string())));
+	let cat = deck.meanings[1].word("es").unwrap();
+	assert_eq!((cat.text.as_str(), cat.audio.as_ref()), ("El gato", None));
+	assert_eq!(deck.meanings[1].note(), Some("Also \"minino\",\nwhen small"));
+
+	assert!(matches!(
+		export.import(&[Field::Skip, Field::Note], &decks),
+		Err(ImportError::Format(..)),
+	));
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes an Anki package holding one Spanish note type, its media and
+/// the review history `revlog` of the first note's card, as (time in
+/// milliseconds, ease).
+fn write_package(path: &Path, revlog: &[(i64, i64)]) {
+	let db_path = path.with_extension("anki2");
+	let _ = fs::remove_file(&db_path);
+	let db = Connection::open(&db_path).unwrap();
+	db.execute_batch(
+		"CREATE TABLE col (decks TEXT, models TEXT);
+		CREATE TABLE notes (id INTEGER, mid INTEGER, flds TEXT);
+		CREATE TABLE cards (id INTEGER, nid INTEGER, did INTEGER);
+		CREATE TABLE revlog (id INTEGER, cid INTEGER, ease INTEGER);",
+	)
+	.unwrap();
+	let decks = r#"{"1": {"name": "Default"}, "7": {"name": "Spanish::Food"}}"#;
+	let models = r#"{"3": {"name": "Basic", "flds": [{"name": "Back", "ord": 1}, {"name": "Front", "ord": 0}]}}"#;
+	db.execute("INSERT INTO col VALUES (?1, ?2)", [decks, models]).unwrap();
+	db.execute(
+		"INSERT INTO notes VALUES (10, 3, ?1), (11, 3, ?2)",
+		["La manzana [sound:manzana.mp3]\x1fThe apple", "El pan<br>\x1fThe bread"],
+	)
+	.unwrap();
+	db.execute_batch("INSERT INTO cards VALUES (20, 10, 7), (21, 11, 7);").unwrap();
+	for (at, ease) in revlog {
+		db.execute("INSERT INTO revlog VALUES (?1, 20, ?2)", [at, ease]).unwrap();
+	}
+	drop(db);
+
+	let mut zip = ZipWriter::new(File::create(path).unwrap());
+	let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+	zip.start_file("collection.anki2", options).unwrap();
+	zip.write_all(&fs::read(&db_path).unwrap()).unwrap();
+	zip.start_file("media", options).unwrap();
+	zip.write_all(br#"{"0": "manzana.mp3", "1": "../escape.mp3"}"#).unwrap();
+	zip.start_file("0", options).unwrap();
+	zip.write_all(b"not really audio").unwrap();
+	zip.finish().unwrap();
+	fs::remove_file(&db_path).unwrap();
+}
+
+#[test]
+fn packages_bring_their_media_and_history() {
+	let dir = std::env::temp_dir().join(format!("corvid-anki-package-{}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	let package = dir.join("spanish.apkg");
+	let day = 86_400_000;
+	// Manual reschedules have ease 0 and are not answers.
+	write_package(&package, &[(20_000 * day, 3), (20_001 * day, 1), (20_001 * day + 1, 0), (20_002 * day, 4)]);
+
+	let export = Export::read(&package).unwrap();
+	assert_eq!(export.name, "Food");
+	assert_eq!(export.fields, ["Front", "Back"]);
+	assert_eq!(export.notes[0].reviews.len(), 3);
+	assert_eq!(export.notes[0].reviews[1].grade, Grade::Again);
+
+	let fields = [Field::Language("es".to_string()), Field::Language("en".to_string())];
+	let imported = export.import(&fields, &dir).unwrap();
+	assert_eq!(imported.review_count(), 3);
+	assert_eq!(imported.missing_media, 0);
+	let deck = Deck::load(&imported.deck.path).unwrap();
+	let apple = deck.meanings[0].word("es").unwrap();
+	assert_eq!(apple.text, "La manzana");
+	assert_eq!(apple.audio.as_deref(), Some(dir.join("food").join("manzana.mp3").as_path()));
+	assert_eq!(fs::read(dir.join("food/manzana.mp3")).unwrap(), b"not really audio");
+	assert_eq!(deck.meanings[1].get_translation("es").as_deref(), Some("El pan"));
+
+	let mut progress = Progress::default();
+	assert_eq!(imported.import_history(&mut progress, "en", "es"), 3);
+	let apple = scheduler::card_key("en", "The apple", "es", "La manzana");
+	let state = progress.scheduler.state(&apple).unwrap();
+	assert_eq!((state.reps, state.lapses, state.due), (1, 1, 20_003));
+	assert_eq!(progress.cards[&apple].seen, 3);
+	assert_eq!(progress.answers.iter().filter(|a| a.card == apple).count(), 3);
+	// Importing again leaves the card alone now that it has a history.
+	assert_eq!(imported.import_history(&mut progress, "en", "es"), 0);
+
+	let mut zip = ZipWriter::new(File::create(&package).unwrap());
+	zip.start_file("collection.anki21b", SimpleFileOptions::default()).unwrap();
+	zip.finish().unwrap();
+	assert!(matches!(Export::read(&package), Err(ImportError::Format(..))));
+	fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Writes a package the way Anki 2.1.50 and later do by default: a
+/// zstd-compressed collection with note types and decks in tables of
+/// their own, a protobuf media list, and an old-style collection that
+/// only asks for a newer Anki.
+fn write_current_package(path: &Path) {
+	let db_path = path.with_extension("anki21b");
+	let _ = fs::remove_file(&db_path);
+	let db = Connection::open(&db_path).unwrap();
+	db.execute_batch(
+		"CREATE TABLE col (decks TEXT, models TEXT);
+		INSERT INTO col VALUES ('', '');
+		CREATE TABLE notetypes (id INTEGER, name TEXT);
+		CREATE TABLE fields (ntid INTEGER, ord INTEGER, name TEXT);
+		CREATE TABLE decks (id INTEGER, name TEXT);
+		CREATE TABLE notes (id INTEGER, mid INTEGER, flds TEXT);
+		CREATE TABLE cards (id INTEGER, nid INTEGER, did INTEGER);
+		CREATE TABLE revlog (id INTEGER, cid INTEGER, ease INTEGER);
+		INSERT INTO notetypes VALUES (3, 'Basic');
+		INSERT INTO fields VALUES (3, 1, 'German'), (3, 0, 'English');
+		INSERT INTO decks VALUES (1, 'Default'), (7, 'German\x1fZoo');
+		INSERT INTO cards VALUES (20, 10, 7);
+		INSERT INTO revlog VALUES (1700000000000, 20, 3);",
+	)
+	.unwrap();
+	db.execute("INSERT INTO notes VALUES (10, 3, ?1)", ["The bear\x1fDer Bär [sound:baer.mp3]"]).unwrap();
+	drop(db);
+
+	let mut entry = vec![0x0a, 8];
+	entry.extend_from_slice(b"baer.mp3");
+	entry.extend_from_slice(&[0x10, 4]);
+	let mut media = vec![0x0a, entry.len() as u8];
+	media.extend(entry);
+
+	let compress = |bytes: &[u8]| compress_to_vec(bytes, CompressionLevel::Fastest);
+	let mut zip = ZipWriter::new(File::create(path).unwrap());
+	let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+	zip.start_file("collection.anki2", options).unwrap();
+	zip.write_all(b"an old-style collection asking for a newer Anki").unwrap();
+	zip.start_file("collection.anki21b", options).unwrap();
+	zip.write_all(&compress(&fs::read(&db_path).unwrap())).unwrap();
+	zip.start_file("media", options).unwrap();
+	zip.write_all(&compress(&media)).unwrap();
+	zip.start_file("0", options).unwrap();
+	zip.write_all(&compress(b"grr!")).unwrap();
+	zip.finish().unwrap();
+	fs::remove_file(&db_path).unwrap();
+}
+
+#[test]
+fn current_packages_are_read() {
+	let dir = std::env::temp_dir().join(format!("corvid-anki-current-{}", std::process::id()));
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir_all(&dir).unwrap();
+	let package = dir.join("german.apkg");
+	write_current_package(&package);
+
+	let export = Export::read(&package).unwrap();
+	assert_eq!(export.name, "Zoo");
+	assert_eq!(export.fields, ["English", "German"]);
+	assert_eq!(export.notes[0].reviews.len(), 1);
+
+	let fields = export.guess_fields();
+	let imported = export.import(&fields, &dir).unwrap();
+	assert_eq!(imported.missing_media, 0);
+	let bear = imported.deck.meanings[0].word("de").unwrap();
+	assert_eq!(bear.text, "Der Bär");
+	assert_eq!(fs::read(dir.join("zoo/baer.mp3")).unwrap(), b"grr!");
+	fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,44 @@
+use corvid_core::conjugation::{self, Tense};
+
+fn present(lang: &str, verb: &str) -> Option<String> {
+	conjugation::conjugate(lang, verb, Tense::Present, conjugation::SLOTS[3])
+}
+
+#[test]
+fn regular_verbs_are_conjugated() {
+	for (lang, verb, nous) in [
+		("es", "hablar", "hablamos"),
+		("es", "dormir", "dormimos"),
+		("fr", "parler", "parlons"),
+		("fr", "créer", "créons"),
+		("fr", "chercher", "cherchons"),
+		("fr", "finir", "finissons"),
+		("fr", "vendre", "vendons"),
+		("fr", "apprendre", "apprenons"),
+		("de", "machen", "machen"),
+		("de", "produzieren", "produzieren"),
+		("de", "einkaufen", "kaufen ein"),
+	] {
+		assert_eq!(present(lang, verb).as_deref(), Some(nous), "{} {}", lang, verb);
+	}
+}
+
+#[test]
+fn verbs_without_a_known_paradigm_have_no_forms() {
+	for (lang, verb) in [
+		("es", "jugar"),
+		("fr", "valoir"),
+		("fr", "falloir"),
+		("fr", "dormir"),
+		("fr", "servir"),
+		("fr", "ouvrir"),
+		("fr", "conduire"),
+		("fr", "acheter"),
+		("fr", "préférer"),
+		("fr", "payer"),
+		("de", "trinken"),
+	] {
+		assert_eq!(present(lang, verb), None, "{} {}", lang, verb);
+		assert_eq!(conjugation::conjugate(lang, verb, Tense::Imperfect, conjugation::SLOTS[0]), None, "{} {}", lang, verb);
+	}
+}
@@ -0,0 +1,28 @@
+use corvid_core::grammar::{Gender, Translation, gender_from_ending};
+
+#[test]
+fn articles_are_split_off_in_any_case() {
+	let word = Translation::parse("de", "DIE ẞ");
+	assert_eq!((word.article.as_deref(), word.lemma.as_str()), (Some("DIE"), "ẞ"));
+	assert_eq!(word.gender, Some(Gender::Feminine));
+
+	let word = Translation::parse("de", "Die İİİİnsel");
+	assert_eq!((word.article.as_deref(), word.lemma.as_str()), (Some("Die"), "İİİİnsel"));
+
+	let word = Translation::parse("fr", "L’oiseau");
+	assert_eq!((word.article.as_deref(), word.lemma.as_str(), word.gender), (Some("L’"), "oiseau", None));
+
+	let word = Translation::parse("de", "İnsel");
+	assert_eq!((word.article, word.lemma.as_str()), (None, "İnsel"));
+	assert_eq!(Translation::parse("es", "Las").lemma, "Las");
+}
+
+#[test]
+fn only_telling_endings_suggest_a_gender() {
+	assert_eq!(gender_from_ending("de", "Zeitung"), Some(Gender::Feminine));
+	assert_eq!(gender_from_ending("de", "Mädchen"), Some(Gender::Neuter));
+	assert_eq!(gender_from_ending("fr", "Nation"), Some(Gender::Feminine));
+	for (lang, lemma) in [("de", "Stier"), ("de", "Wurst"), ("fr", "jument"), ("fr", "squelette"), ("de", "Ung")] {
+		assert_eq!(gender_from_ending(lang, lemma), None, "{lemma}");
+	}
+}
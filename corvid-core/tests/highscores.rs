@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::time::Duration;
+
+use corvid_core::deck::Deck;
+use corvid_core::highscores::{HighScore, HighScores, TABLE_SIZE};
+use corvid_core::language::Languages;
+use corvid_core::storage::Progress;
+use corvid_core::{Exercise, Game, QuizMode, SessionConfig};
+
+fn round(name: &str, points: u32) -> HighScore {
+	HighScore { name: name.to_string(), points, correct: 0, answered: 20, at: 0 }
+}
+
+fn game(deck: &str, config: SessionConfig) -> Game {
+	let config = SessionConfig {
+		deck: deck.to_string(),
+		source_lang: "en".to_string(),
+		target_lang: "es".to_string(),
+		..config
+	};
+	Game::new(config, Vec::new(), Progress::default())
+}
+
+#[test]
+fn tables_keep_the_best_rounds_per_key() {
+	let animals = HighScores::key_for(&game("Animals", SessionConfig::default()));
+	let foods = HighScores::key_for(&game("Foods", SessionConfig::default()));
+	let mut scores = HighScores::default();
+
+	assert_eq!(scores.submit(&animals, round("Ana", 200)), Some(0));
+	assert_eq!(scores.submit(&animals, round("Sam", 300)), Some(0));
+	// Ties go to the earlier round.
+	assert_eq!(scores.submit(&animals, round("Sam", 200)), Some(2));
+	assert_eq!(scores.submit(&foods, round("Ana", 10)), Some(0));
+
+	let names: Vec<(&str, u32)> = scores.table(&animals).iter().map(|s| (s.name.as_str(), s.points)).collect();
+	assert_eq!(names, [("Sam", 300), ("Ana", 200), ("Sam", 200)]);
+
+	for _ in 0..TABLE_SIZE {
+		scores.submit(&animals, round("Ana", 400));
+	}
+	assert_eq!(scores.submit(&animals, round("Sam", 400)), None);
+	assert_eq!(scores.table(&animals).len(), TABLE_SIZE);
+}
+
+#[test]
+fn rounds_set_up_differently_get_their_own_table() {
+	let defaults = || SessionConfig {
+		num_choices: 5,
+		mode: QuizMode::MultipleChoice,
+		time_limit: Some(Duration::from_secs(5)),
+		..SessionConfig::default()
+	};
+	let rounds = [
+		defaults(),
+		SessionConfig { num_choices: 3, ..defaults() },
+		SessionConfig { mode: QuizMode::Typed, ..defaults() },
+		SessionConfig { exercise: Exercise::Gender, ..defaults() },
+		SessionConfig { time_limit: Some(Duration::from_secs(20)), ..defaults() },
+		SessionConfig { lives: Some(3), ..defaults() },
+	];
+	let keys: Vec<String> = rounds.into_iter().map(|config| HighScores::key_for(&game("Animals", config))).collect();
+	for (i, key) in keys.iter().enumerate() {
+		assert!(!keys[..i].contains(key), "{key}");
+	}
+
+	let deck = Deck::parse("name = \"Animals\"\n[[meaning]]\nen = \"The dog\"\nes = \"El perro\"\n", Path::new("animals.toml")).unwrap();
+	let languages = Languages::from_decks(&[deck]);
+	assert_eq!(
+		HighScores::title_for(&game("Animals", SessionConfig { lives: Some(3), ..defaults() }), &languages),
+		"High scores for Animals, translation, survival, 5 s per question, Spanish to English",
+	);
+}
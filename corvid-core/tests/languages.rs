@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use corvid_core::deck::Deck;
+use corvid_core::language::{Direction, Language, Languages};
+
+#[test]
+fn describes_codes_by_subtag() {
+	let es = Language::new("es-MX");
+	assert_eq!(es.name, "Spanish (Mexico)");
+	assert_eq!(es.script, "Latn");
+
+	let sr = Language::new("sr-Latn");
+	assert_eq!(sr.name, "Serbian");
+	assert_eq!(sr.script, "Latn");
+
+	assert_eq!(Language::new("he").direction, Direction::RightToLeft);
+	assert_eq!(Language::new("tlh").name, "tlh");
+}
+
+#[test]
+fn registry_and_pairs_come_from_decks() {
+	let deck = Deck::parse(
+		r#"
+		name = "Numbers"
+
+		[[meaning]]
+		en = "One"
+		ru = "Один"
+
+		[[meaning]]
+		en = "Two"
+		el = "Δύο"
+		"#,
+		Path::new("numbers.toml"),
+	)
+	.unwrap();
+	let languages = Languages::from_decks(std::slice::from_ref(&deck));
+
+	let names: Vec<&str> = languages.all().iter().map(|l| l.name.as_str()).collect();
+	assert_eq!(names, ["English", "Greek", "Russian"]);
+	assert!(deck.covers("en", "ru"));
+	assert!(!deck.covers("ru", "el"));
+	assert!(!deck.covers("en", "en"));
+
+	let mut languages = Languages::default();
+	languages.add_deck(&deck);
+	assert_eq!(languages.name("ru"), "Russian");
+}
@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use corvid_core::deck::Deck;
+use corvid_core::matching::{Board, Pick, Side};
+use corvid_core::storage::Progress;
+
+const DECK: &str = r#"
+name = "Test"
+
+[[meaning]]
+en = "The dog"
+es = "El perro"
+
+[[meaning]]
+en = "The cat"
+es = "El gato"
+
+[[meaning]]
+en = "The house"
+es = "La casa"
+
+[[meaning]]
+en = "Home"
+es = "La casa"
+
+[[meaning]]
+en = "To be (essential/permanent)"
+es = "Ser"
+"#;
+
+fn board() -> Board {
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	Board::new(&deck.name, &deck.meanings, "en", "es", 6, Progress::default())
+}
+
+/// The index of the tile on the other side that belongs with `index`,
+/// going by the word pairs of the deck.
+fn partner(board: &Board, index: usize) -> usize {
+	let tiles = board.tiles();
+	let other = tiles.len() / 2;
+	let range = if tiles[index].side == Side::Source { other..tiles.len() } else { 0..other };
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	let texts: Vec<(String, String)> = deck.meanings
+		.iter()
+		.map(|m| (m.get_translation("en").unwrap(), m.get_translation("es").unwrap()))
+		.collect();
+	range
+		.into_iter()
+		.find(|&j| {
+			let (source, target) = if index < other { (index, j) } else { (j, index) };
+			texts.contains(&(tiles[source].text.clone(), tiles[target].text.clone()))
+		})
+		.unwrap()
+}
+
+#[test]
+fn boards_never_show_the_same_word_twice() {
+	for _ in 0..20 {
+		let board = board();
+		assert_eq!(board.pairs(), 4);
+		let targets: Vec<&str> = board.tiles()
+			.iter()
+			.filter(|t| t.side == Side::Target)
+			.map(|t| t.text.as_str())
+			.collect();
+		assert_eq!(targets.iter().filter(|t| **t == "La casa").count(), 1);
+	}
+}
+
+#[test]
+fn pairs_are_matched_and_mismatches_counted() {
+	let mut board = board();
+	let sources = board.pairs();
+	let wrong = (sources..2 * sources).find(|&j| j != partner(&board, 0)).unwrap();
+
+	assert_eq!(board.pick(0), Pick::Selected);
+	assert_eq!(board.pick(0), Pick::Released);
+	assert_eq!(board.pick(1), Pick::Selected);
+	// Another tile on the same side moves the selection.
+	assert_eq!(board.pick(0), Pick::Selected);
+	assert_eq!(board.pick(wrong), Pick::Mismatched(0, wrong));
+	assert_eq!(board.mismatches(), 1);
+	assert_eq!(board.selected(), None);
+
+	for i in 0..sources {
+		let j = partner(&board, i);
+		assert_eq!(board.pick(j), Pick::Selected);
+		assert_eq!(board.pick(i), Pick::Matched(j, i));
+		assert_eq!(board.pick(i), Pick::Ignored);
+	}
+	assert!(board.is_complete());
+	let elapsed = board.elapsed();
+	assert_eq!(board.elapsed(), elapsed);
+
+	let session = board.progress().sessions.last().unwrap();
+	assert_eq!((session.correct, session.wrong), (sources as u32, 1));
+}
+
+#[test]
+fn too_small_a_deck_deals_an_empty_board() {
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	let board = Board::new("Test", &deck.meanings[..1], "en", "es", 6, Progress::default());
+	assert_eq!((board.pairs(), board.tiles().len()), (0, 0));
+	assert!(!board.is_complete());
+	assert_eq!(Board::new("Test", &deck.meanings, "en", "fr", 6, Progress::default()).pairs(), 0);
+}
@@ -0,0 +1,38 @@
+use corvid_core::profile::{ProfileError, Profiles};
+
+#[test]
+fn profiles_are_created_renamed_and_deleted() {
+	let mut profiles = Profiles::default();
+	let ana = profiles.create("Ana María").unwrap();
+	assert_eq!(ana, "ana-maría");
+	assert_eq!(profiles.create(" ana maría "), Err(ProfileError::NameTaken("ana maría".to_string())));
+	assert_eq!(profiles.create("  "), Err(ProfileError::EmptyName));
+	// Ids stay unique even when the names only differ in punctuation.
+	assert_eq!(profiles.create("Ana-María").unwrap(), "ana-maría-2");
+
+	profiles.set_active(&ana).unwrap();
+	profiles.rename(&ana, "Ana").unwrap();
+	assert_eq!(profiles.active().name, "Ana");
+	assert_eq!(profiles.active().id, ana);
+
+	profiles.remove(&ana).unwrap();
+	assert_eq!(profiles.active().id, "default");
+	profiles.remove("ana-maría-2").unwrap();
+	assert_eq!(profiles.remove("default"), Err(ProfileError::LastProfile));
+	assert_eq!(profiles.all().len(), 1);
+}
+
+#[test]
+fn profiles_round_trip_through_their_file() {
+	let dir = std::env::temp_dir().join(format!("corvid-profiles-{}", std::process::id()));
+	let path = dir.join("profiles.toml");
+
+	let mut profiles = Profiles::load(&path).unwrap();
+	assert_eq!(profiles, Profiles::default());
+	let id = profiles.create("Sam").unwrap();
+	profiles.set_active(&id).unwrap();
+	profiles.save(&path).unwrap();
+	assert_eq!(Profiles::load(&path).unwrap(), profiles);
+
+	let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,591 @@
+use std::path::Path;
+use std::thread::sleep;
+use std::time::Duration;
+
+use corvid_core::deck::Deck;
+use corvid_core::matcher::{self, Tolerance, Verdict};
+use corvid_core::storage::Progress;
+use corvid_core::{Exercise, Game, Picture, QuizMode, RetryPolicy, Score, SessionConfig};
+
+const DECK: &str = r#"
+name = "Test"
+
+[[meaning]]
+en = "The dog"
+es = "El perro"
+de = "Der Hund"
+
+[[meaning]]
+en = "The cat"
+es = "El gato"
+de = "Die Katze"
+
+[[meaning]]
+en = "The house"
+es = "La casa"
+de = "Das Haus"
+
+[[meaning]]
+en = "To be (essential/permanent)"
+es = "Ser"
+de = "Sein"
+note = "Ser is for what something is."
+
+[[meaning]]
+en = "To be (state/location)"
+es = "Estar"
+de = "Sein"
+"#;
+
+fn game(exercise: Exercise, mode: QuizMode) -> Game {
+	game_with_retry(exercise, mode, RetryPolicy::Now)
+}
+
+fn game_with_retry(exercise: Exercise, mode: QuizMode, retry: RetryPolicy) -> Game {
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	let config = SessionConfig {
+		deck: deck.name.clone(),
+		source_lang: "en".to_string(),
+		target_lang: "de".to_string(),
+		num_choices: 3,
+		exercise,
+		mode,
+		retry,
+		length: None,
+		time_limit: None,
+		lives: None,
+		speech: false,
+	};
+	Game::new(config, deck.meanings, Progress::default())
+}
+
+#[test]
+fn multiple_choice_session() {
+	let mut game = game(Exercise::Translation, QuizMode::MultipleChoice);
+	for _ in 0..20 {
+		let q = game.next_question().unwrap();
+		assert_eq!(q.choices.len(), 3);
+		assert!(q.choices.contains(&q.answer));
+		// Only one accepted answer may ever be on offer.
+		assert_eq!(q.choices.iter().filter(|c| q.accepted.contains(c)).count(), 1);
+		let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+		assert!(game.check_answer(right));
+	}
+	assert_eq!(game.score().correct, 20);
+	assert_eq!(game.score().wrong, 0);
+	assert_eq!(game.progress().answers.len(), 20);
+	assert_eq!(game.progress().sessions.last().unwrap().correct, 20);
+}
+
+#[test]
+fn overlapping_meanings_are_all_accepted() {
+	let tolerance = Tolerance::default();
+	let verdict = |input: &str| {
+		let mut game = game(Exercise::Translation, QuizMode::Typed);
+		while game.next_question().unwrap().presented_word != "Sein" {
+			let answer = game.current().unwrap().answer.clone();
+			game.check_typed(&answer, &tolerance);
+		}
+		game.check_typed(input, &tolerance)
+	};
+	assert_eq!(verdict("to be (state/location)"), Verdict::Correct);
+	assert_eq!(verdict("to be (essential/permanent)"), Verdict::Correct);
+	assert_eq!(verdict("the dog"), Verdict::Wrong);
+}
+
+#[test]
+fn gender_session() {
+	let mut game = game(Exercise::Gender, QuizMode::MultipleChoice);
+	for _ in 0..10 {
+		let q = game.next_question().unwrap();
+		assert_eq!(q.choices.len(), 3);
+		let wrong = q.choices.iter().position(|c| *c != q.answer).unwrap();
+		assert!(!game.check_answer(wrong));
+	}
+	assert_eq!(game.score().wrong, 10);
+}
+
+#[test]
+fn nothing_to_ask() {
+	let mut game = game(Exercise::Conjugation, QuizMode::MultipleChoice);
+	let mut empty = Game::new(SessionConfig::default(), Vec::new(), Progress::default());
+	assert!(empty.next_question().is_none());
+	assert!(!empty.check_answer(0));
+	// "Sein" conjugates, so the verb drill has questions here.
+	assert!(game.next_question().is_some());
+}
+
+#[test]
+fn idle_games_open_no_session() {
+	let mut game = Game::idle(Progress::default());
+	assert!(game.next_question().is_none());
+	let progress = game.take_progress();
+	game.replace_progress(progress);
+	assert!(game.progress().sessions.is_empty());
+}
+
+#[test]
+fn missed_questions_come_back() {
+	let mut game = game(Exercise::Translation, QuizMode::MultipleChoice);
+	let card = game.next_question().unwrap().card.clone();
+	game.check_typed("wrong", &Tolerance::default());
+	assert_eq!(game.next_question().unwrap().card, card);
+
+	let mut game = game_with_retry(Exercise::Translation, QuizMode::Typed, RetryPolicy::Later);
+	let card = game.next_question().unwrap().card.clone();
+	game.check_typed("wrong", &Tolerance::default());
+	for _ in 0..3 {
+		let q = game.next_question().unwrap();
+		assert_ne!(q.card, card);
+		let answer = q.answer.clone();
+		game.check_typed(&answer, &Tolerance::default());
+	}
+	assert_eq!(game.next_question().unwrap().card, card);
+}
+
+#[test]
+fn notes_are_read_from_the_deck() {
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	let ser = deck.meanings.iter().find(|m| m.get_translation("es").as_deref() == Some("Ser")).unwrap();
+	assert_eq!(ser.note(), Some("Ser is for what something is."));
+	assert!(ser.word("note").is_none());
+}
+
+#[test]
+fn readings_are_shown_and_accepted() {
+	let deck = Deck::parse(
+		r#"
+		name = "Numbers"
+
+		[[meaning]]
+		en = "One"
+		ja = { text = "一", reading = "いち", romanization = "ichi" }
+		"#,
+		Path::new("numbers.toml"),
+	)
+	.unwrap();
+	let config = |source: &str, target: &str| SessionConfig {
+		source_lang: source.to_string(),
+		target_lang: target.to_string(),
+		mode: QuizMode::Typed,
+		..SessionConfig::default()
+	};
+
+	let mut game = Game::new(config("en", "ja"), deck.meanings.clone(), Progress::default());
+	let q = game.next_question().unwrap();
+	assert_eq!(q.presented_reading.as_deref(), Some("いち"));
+	assert_eq!(q.presented_romanization.as_deref(), Some("ichi"));
+
+	let tolerance = Tolerance::default();
+	let verdict = |input: &str| {
+		let mut game = Game::new(config("ja", "en"), deck.meanings.clone(), Progress::default());
+		game.next_question();
+		game.check_typed(input, &tolerance)
+	};
+	assert_eq!(verdict("一"), Verdict::Correct);
+	assert_eq!(verdict("いち"), Verdict::Correct);
+	assert_eq!(verdict("Ichi"), Verdict::Correct);
+	assert_eq!(verdict("ni"), Verdict::Wrong);
+}
+
+#[test]
+fn other_regional_variants_are_accepted_as_regional() {
+	let deck = Deck::parse(
+		r#"
+		name = "Food"
+
+		[[meaning]]
+		en = "The potato"
+		es = "La patata"
+		es-419 = "La papa"
+
+		[[meaning]]
+		en = "The tomato"
+		es = "El tomate"
+		"#,
+		Path::new("food.toml"),
+	)
+	.unwrap();
+	let tolerance = Tolerance::default();
+	let config = SessionConfig {
+		source_lang: "es-419".to_string(),
+		target_lang: "en".to_string(),
+		mode: QuizMode::Typed,
+		retry: RetryPolicy::MoveOn,
+		..SessionConfig::default()
+	};
+	for potato in ["la patata", "papa"] {
+		let mut game = Game::new(config.clone(), deck.meanings.clone(), Progress::default());
+		for _ in 0..4 {
+			let q = game.next_question().unwrap();
+			match q.presented_word.as_str() {
+				"The potato" => {
+					assert_eq!(q.answer, "La papa");
+					let expected = if potato == "papa" { Verdict::Correct } else { Verdict::Regional };
+					assert_eq!(game.check_typed(potato, &tolerance), expected);
+				}
+				// No regional word, so the base language is asked.
+				_ => assert_eq!(game.check_typed("el tomate", &tolerance), Verdict::Correct),
+			}
+		}
+	}
+}
+
+#[test]
+fn typed_articles_have_to_be_right() {
+	let tolerance = Tolerance::default();
+	assert_eq!(matcher::grade("la chat", "Le chat", "fr", &tolerance), Verdict::Wrong);
+	assert_eq!(matcher::grade("le chot", "Le chat", "fr", &tolerance), Verdict::NearMiss);
+	assert_eq!(matcher::grade("chat", "Le chat", "fr", &tolerance), Verdict::Correct);
+	assert_eq!(matcher::grade("el perro", "Perro", "es", &tolerance), Verdict::Correct);
+	let strict = Tolerance { optional_articles: false, ..tolerance };
+	assert_eq!(matcher::grade("perro", "El perro", "es", &strict), Verdict::Wrong);
+}
+
+#[test]
+fn other_words_of_the_deck_are_no_typos() {
+	let deck = Deck::parse(
+		r#"
+		name = "Animals"
+
+		[[meaning]]
+		en = "The cat"
+		es = "El gato"
+
+		[[meaning]]
+		en = "The duck"
+		es = "El pato"
+		"#,
+		Path::new("animals.toml"),
+	)
+	.unwrap();
+	let config = SessionConfig {
+		source_lang: "es".to_string(),
+		target_lang: "en".to_string(),
+		mode: QuizMode::Typed,
+		..SessionConfig::default()
+	};
+	let tolerance = Tolerance::default();
+	for typed in ["pato", "gatto"] {
+		let mut game = Game::new(config.clone(), deck.meanings.clone(), Progress::default());
+		while game.next_question().unwrap().presented_word != "The cat" {
+			let answer = game.current().unwrap().answer.clone();
+			game.check_typed(&answer, &tolerance);
+		}
+		let expected = if typed == "pato" { Verdict::Wrong } else { Verdict::NearMiss };
+		assert_eq!(game.check_typed(typed, &tolerance), expected);
+	}
+}
+
+#[test]
+fn typed_genders_are_graded_exactly() {
+	let deck = Deck::parse(
+		r#"
+		name = "Animals"
+
+		[[meaning]]
+		en = "The dog"
+		fr = "Le chien"
+		"#,
+		Path::new("animals.toml"),
+	)
+	.unwrap();
+	let config = SessionConfig {
+		source_lang: "en".to_string(),
+		target_lang: "fr".to_string(),
+		exercise: Exercise::Gender,
+		mode: QuizMode::Typed,
+		..SessionConfig::default()
+	};
+	let tolerance = Tolerance::default();
+	for (typed, expected) in [("la", Verdict::Wrong), ("Le", Verdict::Correct)] {
+		let mut game = Game::new(config.clone(), deck.meanings.clone(), Progress::default());
+		assert_eq!(game.next_question().unwrap().answer, "le");
+		assert_eq!(game.check_typed(typed, &tolerance), expected);
+	}
+}
+
+#[test]
+fn other_forms_of_the_verb_are_no_typos() {
+	let deck = Deck::parse(
+		r#"
+		name = "Verbs"
+
+		[[meaning]]
+		en = "To speak"
+		es = "Hablar"
+		"#,
+		Path::new("verbs.toml"),
+	)
+	.unwrap();
+	let config = SessionConfig {
+		source_lang: "en".to_string(),
+		target_lang: "es".to_string(),
+		exercise: Exercise::Conjugation,
+		mode: QuizMode::Typed,
+		..SessionConfig::default()
+	};
+	let tolerance = Tolerance::default();
+	for (typed, expected) in [("habla", Verdict::Wrong), ("hablen", Verdict::NearMiss)] {
+		let mut game = Game::new(config.clone(), deck.meanings.clone(), Progress::default());
+		while game.next_question().unwrap().presented_word != "hablar — ellos/ellas, presente" {
+			let answer = game.current().unwrap().answer.clone();
+			game.check_typed(&answer, &tolerance);
+		}
+		assert_eq!(game.check_typed(typed, &tolerance), expected);
+	}
+}
+
+#[test]
+fn questions_are_only_answered_once() {
+	let mut game = game(Exercise::Translation, QuizMode::MultipleChoice);
+	let q = game.next_question().unwrap();
+	let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+	assert!(game.check_answer(right));
+	assert!(!game.check_answer(right));
+	let answer = game.current().unwrap().answer.clone();
+	assert_eq!(game.check_typed(&answer, &Tolerance::default()), Verdict::Wrong);
+	assert_eq!((game.score().correct, game.score().wrong), (1, 0));
+	assert_eq!(game.summary().longest_streak, 1);
+	assert_eq!(game.progress().answers.len(), 1);
+}
+
+#[test]
+fn sessions_end_after_their_length() {
+	let mut game = Game::new(
+		SessionConfig { length: Some(3), ..SessionConfig::default() },
+		Deck::parse(DECK, Path::new("test.toml")).unwrap().meanings,
+		Progress::default(),
+	);
+	for _ in 0..3 {
+		assert!(!game.is_finished());
+		game.next_question().unwrap();
+		game.check_answer(0);
+	}
+	assert!(game.is_finished());
+	assert!(game.next_question().is_none());
+}
+
+#[test]
+fn summary_and_review_of_missed_questions() {
+	let mut game = game_with_retry(Exercise::Translation, QuizMode::MultipleChoice, RetryPolicy::MoveOn);
+	let mut missed = Vec::new();
+	for i in 0..6 {
+		let q = game.next_question().unwrap();
+		let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+		let wrong = q.choices.iter().position(|c| !q.accepted.contains(c)).unwrap();
+		// Right, right, right, wrong, right, wrong.
+		if i == 3 || i == 5 {
+			if !missed.contains(&q.card) {
+				missed.push(q.card.clone());
+			}
+			game.check_answer(wrong);
+		} else {
+			game.check_answer(right);
+		}
+	}
+	let summary = game.summary();
+	assert_eq!((summary.answered, summary.correct, summary.longest_streak), (6, 4, 3));
+	assert_eq!(summary.accuracy(), Some(4.0 / 6.0));
+	assert!(summary.average_time.is_some());
+	let cards: Vec<&String> = summary.missed.iter().map(|q| &q.card).collect();
+	assert_eq!(cards, missed.iter().collect::<Vec<_>>());
+
+	let mut review = game.review_missed().unwrap();
+	assert!(game.progress().answers.is_empty());
+	while let Some(q) = review.next_question() {
+		assert!(missed.contains(&q.card));
+		let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+		review.check_answer(right);
+	}
+	assert!(review.is_finished());
+	assert_eq!(review.score().correct as usize, missed.len());
+	assert!(review.review_missed().is_none());
+}
+
+#[test]
+fn timed_questions_run_out_and_fast_answers_score_more() {
+	let config = |limit: u64| SessionConfig {
+		num_choices: 3,
+		time_limit: Some(Duration::from_millis(limit)),
+		..SessionConfig::default()
+	};
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+
+	let mut game = Game::new(config(60_000), deck.meanings.clone(), Progress::default());
+	let q = game.next_question().unwrap();
+	let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+	assert!(game.time_left().unwrap() > Duration::from_secs(59));
+	assert!(game.check_answer(right));
+	assert!(game.time_left().is_none());
+	assert!(game.score().points > 19);
+
+	let mut game = Game::new(config(1), deck.meanings, Progress::default());
+	let q = game.next_question().unwrap();
+	let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+	sleep(Duration::from_millis(5));
+	assert_eq!(game.time_left(), Some(Duration::ZERO));
+	// Too late, even though it is right.
+	assert!(!game.check_answer(right));
+	assert_eq!(game.score(), Score { correct: 0, wrong: 1, points: 0 });
+	assert_eq!(game.summary().points, Some(0));
+	game.time_out();
+	assert_eq!(game.score().wrong, 1);
+}
+
+#[test]
+fn survival_runs_grow_harder_until_the_last_life() {
+	let numbers = ["One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten"];
+	let toml: String = numbers
+		.iter()
+		.enumerate()
+		.map(|(i, n)| format!("[[meaning]]\nen = \"{}\"\nes = \"{}\"\n", n, i + 1))
+		.collect();
+	let deck = Deck::parse(&format!("name = \"Numbers\"\n{}", toml), Path::new("numbers.toml")).unwrap();
+	let config = SessionConfig { num_choices: 3, lives: Some(3), ..SessionConfig::default() };
+	let mut game = Game::new(config, deck.meanings, Progress::default());
+
+	for expected in [3, 3, 3, 4, 4, 4, 5] {
+		let q = game.next_question().unwrap();
+		assert_eq!(q.choices.len(), expected);
+		let right = q.choices.iter().position(|c| *c == q.answer).unwrap();
+		game.check_answer(right);
+	}
+	for lives in [2, 1, 0] {
+		let q = game.next_question().unwrap();
+		let wrong = q.choices.iter().position(|c| *c != q.answer).unwrap();
+		game.check_answer(wrong);
+		assert_eq!(game.lives_left(), Some(lives));
+	}
+	assert!(game.is_finished());
+	assert!(game.next_question().is_none());
+	assert_eq!(game.summary().points, Some(70));
+	assert_eq!(game.choices_offered(), 3);
+}
+
+#[test]
+fn similar_words_are_told_apart() {
+	assert_eq!(matcher::similarity("Perro", "perro"), 1.0);
+	assert!(matcher::similarity("Perro", "Pero") > matcher::similarity("Perro", "Gato"));
+}
+
+#[test]
+fn listening_asks_only_words_with_a_recording() {
+	let deck = Deck::parse(
+		r#"
+		name = "Animals"
+
+		[[meaning]]
+		en = "The dog"
+		es = { text = "El perro", audio = "audio/perro.ogg" }
+
+		[[meaning]]
+		en = "The cat"
+		es = { text = "El gato", audio = "/srv/clips/gato.ogg" }
+
+		[[meaning]]
+		en = "The bird"
+		es = "El pájaro"
+		"#,
+		Path::new("decks/animals.toml"),
+	)
+	.unwrap();
+	let config = SessionConfig {
+		source_lang: "en".to_string(),
+		target_lang: "es".to_string(),
+		exercise: Exercise::Listening,
+		retry: RetryPolicy::MoveOn,
+		..SessionConfig::default()
+	};
+	let mut game = Game::new(config, deck.meanings, Progress::default());
+	for _ in 0..6 {
+		let q = game.next_question().unwrap();
+		let audio = q.presented_audio.clone().unwrap();
+		match q.answer.as_str() {
+			"El perro" => assert_eq!(audio, Path::new("decks/audio/perro.ogg")),
+			"El gato" => assert_eq!(audio, Path::new("/srv/clips/gato.ogg")),
+			other => panic!("{} has no recording", other),
+		}
+		assert_eq!(q.choices.len(), 2);
+		let answer = q.answer.to_lowercase();
+		assert_eq!(game.check_typed(&answer, &Tolerance::default()), Verdict::Correct);
+	}
+	assert_eq!(game.answer_lang(), "es");
+}
+
+#[test]
+fn speech_lets_listening_ask_every_word() {
+	let deck = Deck::parse(DECK, Path::new("test.toml")).unwrap();
+	let config = SessionConfig {
+		source_lang: "en".to_string(),
+		target_lang: "es".to_string(),
+		exercise: Exercise::Listening,
+		speech: true,
+		..SessionConfig::default()
+	};
+	let mut game = Game::new(config, deck.meanings.clone(), Progress::default());
+	let q = game.next_question().unwrap();
+	assert!(q.presented_audio.is_none());
+	let word = q.answer.clone();
+	assert_eq!(game.spoken_prompt(), Some((word.as_str(), "es")));
+
+	let config = SessionConfig { exercise: Exercise::Gender, ..SessionConfig::default() };
+	let mut game = Game::new(config, deck.meanings, Progress::default());
+	game.next_question().unwrap();
+	assert_eq!(game.spoken_prompt(), None);
+}
+
+#[test]
+fn pictures_are_picked_for_words_that_have_one() {
+	let deck = Deck::parse(
+		r#"
+		name = "Animals"
+
+		[[meaning]]
+		en = "The dog"
+		es = "El perro"
+		image = "images/missing-dog.png"
+		emoji = "🐕"
+
+		[[meaning]]
+		en = "The cat"
+		es = "El gato"
+		emoji = "🐈"
+
+		[[meaning]]
+		en = "The hound"
+		es = "El sabueso"
+		emoji = "🐕"
+
+		[[meaning]]
+		en = "The bird"
+		es = "El pájaro"
+		"#,
+		Path::new("decks/animals.toml"),
+	)
+	.unwrap();
+	let config = SessionConfig {
+		exercise: Exercise::Pictures,
+		mode: QuizMode::Typed,
+		retry: RetryPolicy::MoveOn,
+		..SessionConfig::default()
+	};
+	let mut game = Game::new(config, deck.meanings, Progress::default());
+	assert_eq!(game.mode(), QuizMode::MultipleChoice);
+	for _ in 0..6 {
+		let q = game.next_question().unwrap();
+		assert!(q.presented_picture.is_none());
+		let pictures: Vec<Picture> = q.choices.iter().map(|c| q.choice_picture(c).unwrap().clone()).collect();
+		// The missing image falls back to the emoji, and the two dogs are
+		// never offered together.
+		match q.answer.as_str() {
+			"El perro" | "El sabueso" => assert_eq!(pictures.len(), 2),
+			"El gato" => assert_eq!(pictures.len(), 2),
+			other => panic!("{} has no picture", other),
+		}
+		assert!(pictures.contains(&Picture::Emoji("🐈".to_string())));
+		assert!(!pictures.contains(&Picture::Image("decks/images/missing-dog.png".into())));
+		let answer = q.choices.iter().position(|c| *c == q.answer).unwrap();
+		assert!(game.check_answer(answer));
+	}
+}
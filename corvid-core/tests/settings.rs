@@ -0,0 +1,120 @@
+use std::fs;
+use std::time::Duration;
+
+use corvid_core::persist::LoadError;
+use corvid_core::settings::{self, Challenge, Settings, Voice};
+use corvid_core::storage::{self, Progress};
+use corvid_core::{Exercise, QuizMode};
+
+#[test]
+fn settings_round_trip_through_the_config_file() {
+	let dir = std::env::temp_dir().join(format!("corvid-settings-{}", std::process::id()));
+	let path = dir.join("settings.toml");
+
+	assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+	let settings = Settings {
+		target_lang: "de".to_string(),
+		reverse: true,
+		mode: QuizMode::Typed,
+		default_deck: Some("Animals".to_string()),
+		session_length: Some(20),
+		..Settings::default()
+	};
+	settings.save(&path).unwrap();
+	assert_eq!(Settings::load(&path).unwrap(), settings);
+
+	// Missing keys keep their defaults.
+	fs::write(&path, "version = 1\ntarget_lang = \"fr\"\n").unwrap();
+	let loaded = Settings::load(&path).unwrap();
+	assert_eq!(loaded.target_lang, "fr");
+	assert_eq!(loaded.exercise, Exercise::Translation);
+
+	let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn reverse_only_applies_to_translation() {
+	let settings = Settings { reverse: true, ..Settings::default() };
+	let config = settings.session_config("Animals");
+	assert_eq!((config.source_lang.as_str(), config.target_lang.as_str()), ("es", "en"));
+
+	let settings = Settings { exercise: Exercise::Gender, ..settings };
+	let config = settings.session_config("Animals");
+	assert_eq!((config.source_lang.as_str(), config.target_lang.as_str()), ("en", "es"));
+}
+
+#[test]
+fn challenges_shape_the_session() {
+	let settings = Settings { num_choices: 7, session_length: Some(50), ..Settings::default() };
+	let config = settings.session_config("Animals");
+	assert_eq!((config.num_choices, config.length, config.time_limit, config.lives), (7, Some(50), None, None));
+
+	let settings = Settings { challenge: Challenge::Lightning, time_limit: 10, ..settings };
+	let config = settings.session_config("Animals");
+	assert_eq!(config.length, Some(settings::LIGHTNING_QUESTIONS));
+	assert_eq!(config.time_limit, Some(Duration::from_secs(10)));
+
+	let settings = Settings { challenge: Challenge::Survival, ..settings };
+	let config = settings.session_config("Animals");
+	assert_eq!((config.num_choices, config.length, config.time_limit), (settings::SURVIVAL_CHOICES, None, None));
+	assert_eq!(config.lives, Some(settings::SURVIVAL_LIVES));
+}
+
+#[test]
+fn unreadable_settings_are_moved_aside() {
+	let dir = std::env::temp_dir().join(format!("corvid-broken-settings-{}", std::process::id()));
+	let path = dir.join("settings.toml");
+	fs::create_dir_all(&dir).unwrap();
+	fs::write(&path, "mode = 3\n").unwrap();
+
+	let Err(LoadError::Unparsable { backup: Some(backup), .. }) = Settings::load(&path) else {
+		panic!("the broken file is reported and moved aside");
+	};
+	assert!(!path.exists());
+	assert_eq!(fs::read_to_string(&backup).unwrap(), "mode = 3\n");
+	assert_eq!(Settings::load(&path).unwrap(), Settings::default());
+
+	let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn state_from_a_newer_corvid_is_moved_aside() {
+	let dir = std::env::temp_dir().join(format!("corvid-newer-settings-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let settings_path = dir.join("settings.toml");
+	let progress_path = dir.join("progress.json");
+	let newer = settings::SETTINGS_VERSION + 1;
+	fs::write(&settings_path, format!("version = {}\nmode = \"typed\"\n", newer)).unwrap();
+	fs::write(&progress_path, format!("{{\"version\": {}}}", storage::FORMAT_VERSION + 1)).unwrap();
+
+	assert!(matches!(Settings::load(&settings_path), Err(LoadError::Unparsable { backup: Some(_), .. })));
+	assert!(matches!(Progress::load(&progress_path), Err(LoadError::Unparsable { backup: Some(_), .. })));
+	// Saving the defaults now leaves the newer files alone.
+	Settings::default().save(&settings_path).unwrap();
+	let moved: Vec<String> = fs::read_dir(&dir)
+		.unwrap()
+		.map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+		.collect();
+	assert!(moved.iter().any(|text| text.contains(&format!("version = {}", newer))));
+	assert_eq!(moved.len(), 3);
+
+	// A file without a version has the first layout.
+	fs::write(&settings_path, "mode = \"typed\"\n").unwrap();
+	let loaded = Settings::load(&settings_path).unwrap();
+	assert_eq!((loaded.version, loaded.mode), (settings::SETTINGS_VERSION, QuizMode::Typed));
+
+	let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn voices_fall_back_to_the_base_language() {
+	let mut settings = Settings::default();
+	let slow = Voice { name: Some("es+f3".to_string()), rate: 0.7 };
+	settings.voices.insert("es".to_string(), slow.clone());
+	assert_eq!(settings.voice("es-419"), slow);
+	assert_eq!(settings.voice("de"), Voice::default());
+
+	let text = toml::to_string(&settings).unwrap();
+	assert!(text.contains("[voices.es]"));
+}
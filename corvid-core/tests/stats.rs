@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use corvid_core::deck::Deck;
+use corvid_core::scheduler::{self, Grade};
+use corvid_core::stats::{self, Mastery};
+use corvid_core::storage::Progress;
+
+#[test]
+fn days_and_mastery_come_from_progress() {
+	let deck = Deck::parse(
+		r#"
+		name = "Test"
+
+		[[meaning]]
+		en = "The dog"
+		es = "El perro"
+
+		[[meaning]]
+		en = "The cat"
+		es = "El gato"
+
+		[[meaning]]
+		en = "The house"
+		es = "La casa"
+		"#,
+		Path::new("test.toml"),
+	)
+	.unwrap();
+	let dog = scheduler::card_key("en", "The dog", "es", "El perro");
+	// Asked the other way round, the prompt is English.
+	let cat = scheduler::card_key("es", "El gato", "en", "The cat");
+
+	let mut progress = Progress::default();
+	progress.start_session("Test", "en", "es", 3);
+	for _ in 0..4 {
+		progress.record_answer(&dog, "The dog", Grade::Good);
+	}
+	progress.record_answer(&cat, "La casa", Grade::Again);
+
+	let days = stats::daily(&progress);
+	assert_eq!(days.len(), 1);
+	assert_eq!((days[0].reviews, days[0].correct), (5, 4));
+	assert_eq!(days[0].accuracy(), 0.8);
+
+	let counts = stats::mastery(&progress, &deck.meanings);
+	assert_eq!(counts[Mastery::New as usize], 1);
+	assert_eq!(counts[Mastery::Learning as usize], 1);
+	assert_eq!(counts[Mastery::Familiar as usize], 0);
+	assert_eq!(counts[Mastery::Mastered as usize], 1);
+}
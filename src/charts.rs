@@ -0,0 +1,178 @@
+use gtk::cairo::{Context, Error};
+
+use corvid_core::scheduler::Day;
+use corvid_core::stats::{DayCount, Mastery};
+
+/// Days covered by the reviews and accuracy charts.
+pub const RECENT_DAYS: Day = 30;
+/// Weeks covered by the study calendar.
+pub const CALENDAR_WEEKS: Day = 26;
+
+const PAD: f64 = 24.0;
+const TEXT: (f64, f64, f64) = (0.5, 0.5, 0.5);
+const BAR: (f64, f64, f64) = (0.2, 0.5, 0.85);
+const LINE: (f64, f64, f64) = (0.2, 0.65, 0.3);
+
+fn set_color(cr: &Context, (r, g, b): (f64, f64, f64)) {
+	cr.set_source_rgb(r, g, b);
+}
+
+fn label(cr: &Context, x: f64, y: f64, text: &str) -> Result<(), Error> {
+	set_color(cr, TEXT);
+	cr.set_font_size(11.0);
+	cr.move_to(x, y);
+	cr.show_text(text)
+}
+
+/// Draws the frame shared by the day charts: the baseline and the first
+/// and last day under it.
+fn day_axis(cr: &Context, width: f64, height: f64, first: Day, last: Day) -> Result<(), Error> {
+	set_color(cr, TEXT);
+	cr.set_line_width(1.0);
+	cr.move_to(PAD, height - PAD);
+	cr.line_to(width - PAD, height - PAD);
+	cr.stroke()?;
+	label(cr, PAD, height - 6.0, &format!("{} days ago", last - first))?;
+	let extents = cr.text_extents("today")?;
+	label(cr, width - PAD - extents.width(), height - 6.0, "today")
+}
+
+/// One bar per day for the last [`RECENT_DAYS`] days.
+pub fn reviews_per_day(cr: &Context, width: f64, height: f64, days: &[DayCount], today: Day) -> Result<(), Error> {
+	let first = today - RECENT_DAYS + 1;
+	let recent: Vec<&DayCount> = days.iter().filter(|d| d.day >= first).collect();
+	let max = recent.iter().map(|d| d.reviews).max().unwrap_or(0);
+	day_axis(cr, width, height, first, today)?;
+	if max == 0 {
+		return label(cr, PAD, PAD, &format!("Nothing answered in the last {} days.", RECENT_DAYS));
+	}
+	label(cr, PAD, 14.0, &format!("{} answers on the busiest day", max))?;
+
+	let slot = (width - 2.0 * PAD) / RECENT_DAYS as f64;
+	let top = PAD;
+	let bottom = height - PAD;
+	set_color(cr, BAR);
+	for d in recent {
+		let x = PAD + (d.day - first) as f64 * slot;
+		let h = (bottom - top) * f64::from(d.reviews) / f64::from(max);
+		cr.rectangle(x + 1.0, bottom - h, (slot - 2.0).max(1.0), h);
+	}
+	cr.fill()
+}
+
+/// Share of right answers on each day something was answered, over the
+/// last [`RECENT_DAYS`] days.
+pub fn accuracy_over_time(cr: &Context, width: f64, height: f64, days: &[DayCount], today: Day) -> Result<(), Error> {
+	let first = today - RECENT_DAYS + 1;
+	let recent: Vec<&DayCount> = days.iter().filter(|d| d.day >= first).collect();
+	day_axis(cr, width, height, first, today)?;
+	if recent.is_empty() {
+		return label(cr, PAD, PAD, &format!("Nothing answered in the last {} days.", RECENT_DAYS));
+	}
+	label(cr, PAD, 14.0, "100%")?;
+
+	let span = (RECENT_DAYS - 1) as f64;
+	let top = PAD;
+	let bottom = height - PAD;
+	let point = |d: &DayCount| {
+		let x = PAD + (d.day - first) as f64 / span * (width - 2.0 * PAD);
+		(x, bottom - (bottom - top) * d.accuracy())
+	};
+	set_color(cr, LINE);
+	cr.set_line_width(2.0);
+	for (i, d) in recent.iter().enumerate() {
+		let (x, y) = point(d);
+		if i == 0 {
+			cr.move_to(x, y);
+		} else {
+			cr.line_to(x, y);
+		}
+	}
+	cr.stroke()?;
+	for d in &recent {
+		let (x, y) = point(d);
+		cr.arc(x, y, 3.0, 0.0, std::f64::consts::TAU);
+		cr.fill()?;
+	}
+	Ok(())
+}
+
+fn mastery_color(level: Mastery) -> (f64, f64, f64) {
+	match level {
+		Mastery::New => (0.75, 0.75, 0.75),
+		Mastery::Learning => (0.9, 0.55, 0.2),
+		Mastery::Familiar => (0.95, 0.8, 0.25),
+		Mastery::Mastered => (0.25, 0.65, 0.3),
+	}
+}
+
+/// A topic's name and its number of words at each [`Mastery`] level.
+pub type TopicMastery = (String, [u32; 4]);
+
+/// Row height of [`mastery_by_topic`], so the page can size the chart.
+pub const MASTERY_ROW: f64 = 28.0;
+
+/// One stacked bar per topic, split by mastery level, under a legend.
+pub fn mastery_by_topic(cr: &Context, width: f64, topics: &[TopicMastery]) -> Result<(), Error> {
+	let mut x = PAD;
+	for level in Mastery::ALL {
+		set_color(cr, mastery_color(level));
+		cr.rectangle(x, 6.0, 10.0, 10.0);
+		cr.fill()?;
+		label(cr, x + 14.0, 15.0, level.name())?;
+		x += 14.0 + cr.text_extents(level.name())?.width() + 16.0;
+	}
+
+	let names_width = topics
+		.iter()
+		.map(|(name, _)| cr.text_extents(name).map(|e| e.width()))
+		.try_fold(0.0_f64, |w, e| e.map(|e| w.max(e)))?;
+	let bar_x = PAD + names_width + 8.0;
+	let bar_width = (width - PAD - bar_x).max(1.0);
+	for (row, (name, counts)) in topics.iter().enumerate() {
+		let y = MASTERY_ROW * (row as f64 + 1.0);
+		label(cr, PAD, y + 14.0, name)?;
+		let total: u32 = counts.iter().sum();
+		if total == 0 {
+			continue;
+		}
+		let mut x = bar_x;
+		for level in Mastery::ALL {
+			let w = bar_width * f64::from(counts[level as usize]) / f64::from(total);
+			set_color(cr, mastery_color(level));
+			cr.rectangle(x, y + 2.0, w, MASTERY_ROW - 8.0);
+			cr.fill()?;
+			x += w;
+		}
+	}
+	Ok(())
+}
+
+/// A calendar of the last [`CALENDAR_WEEKS`] weeks, one column per week
+/// from Monday to Sunday, shaded by the number of answers that day.
+pub fn study_calendar(cr: &Context, width: f64, height: f64, days: &[DayCount], today: Day) -> Result<(), Error> {
+	// Day 0, 1970-01-01, was a Thursday.
+	let weekday = |day: Day| (day + 3).rem_euclid(7);
+	let first = today - weekday(today) - (CALENDAR_WEEKS - 1) * 7;
+	let max = days.iter().filter(|d| d.day >= first).map(|d| d.reviews).max().unwrap_or(0);
+
+	let cell = ((width - 2.0 * PAD) / CALENDAR_WEEKS as f64)
+		.min((height - PAD) / 7.0)
+		.floor();
+	for day in first..=today {
+		let reviews = days.iter().find(|d| d.day == day).map_or(0, |d| d.reviews);
+		let x = PAD + ((day - first) / 7) as f64 * cell;
+		let y = weekday(day) as f64 * cell;
+		if reviews == 0 {
+			cr.set_source_rgb(0.85, 0.85, 0.85);
+		} else {
+			// Busier days get a deeper green.
+			let strength = 0.3 + 0.7 * f64::from(reviews) / f64::from(max);
+			cr.set_source_rgb(0.9 - 0.7 * strength, 0.95 - 0.4 * strength, 0.9 - 0.7 * strength);
+		}
+		cr.rectangle(x + 1.0, y + 1.0, cell - 2.0, cell - 2.0);
+		cr.fill()?;
+	}
+	let studied = days.iter().filter(|d| d.day >= first).count();
+	label(cr, PAD, 7.0 * cell + 16.0, &format!("{} study days in the last {} weeks", studied, CALENDAR_WEEKS))
+}
@@ -0,0 +1,130 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, Label, Orientation, PolicyType, ScrolledWindow};
+
+use corvid_core::deck::Deck;
+use corvid_core::Game;
+
+use crate::App;
+use crate::match_view::MatchView;
+use crate::prefs::Prefs;
+use crate::quiz_view::QuizView;
+
+/// The page where a round of the selected topic is set up and started.
+#[derive(Clone)]
+pub struct DifficultyView {
+	app: App,
+	prefs: Prefs,
+	/// The quiz buttons, by their number of choices.
+	difficulty_btns: [(u32, Button); 3],
+}
+
+impl DifficultyView {
+	pub fn new(app: &App, prefs: &Prefs, quiz_view: &QuizView, match_view: &MatchView) -> Self {
+		let easy_btn = Button::with_label("Easy (3 choices)");
+		let medium_btn = Button::with_label("Medium (5 choices)");
+		let hard_btn = Button::with_label("Hard (7 choices)");
+		let match_btn = Button::with_label("Match pairs");
+		let back_btn_difficulty = Button::with_label("Back");
+		for btn in &[&easy_btn, &medium_btn, &hard_btn, &match_btn, &back_btn_difficulty] {
+			btn.set_margin_top(12);
+			btn.set_margin_bottom(12);
+			btn.set_margin_start(12);
+			btn.set_margin_end(12);
+		}
+
+		let difficulty_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.vexpand(true)
+			.build();
+
+		for (title, combo) in prefs.round_settings() {
+			let label = Label::new(Some(title));
+			label.set_margin_top(12);
+			label.set_margin_bottom(12);
+			label.set_margin_start(12);
+			label.set_margin_end(12);
+			combo.set_margin_top(12);
+			combo.set_margin_bottom(12);
+			combo.set_margin_start(12);
+			combo.set_margin_end(12);
+			difficulty_box.append(&label);
+			difficulty_box.append(combo);
+		}
+		difficulty_box.append(&easy_btn);
+		difficulty_box.append(&medium_btn);
+		difficulty_box.append(&hard_btn);
+		difficulty_box.append(&match_btn);
+
+		let difficulty_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.min_content_width(360)
+			.vexpand(true)
+			.child(&difficulty_box)
+			.build();
+
+		let difficulty_container = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.hexpand(true)
+			.vexpand(true)
+			.build();
+
+		difficulty_container.append(&back_btn_difficulty);
+		difficulty_container.append(&difficulty_scroll);
+		app.stack.add_named(&difficulty_container, Some("difficulty_selection"));
+
+		let view = DifficultyView {
+			app: app.clone(),
+			prefs: prefs.clone(),
+			difficulty_btns: [(3, easy_btn), (5, medium_btn), (7, hard_btn)],
+		};
+		view.mark(app.settings.borrow().num_choices);
+
+		for (difficulty, btn) in &view.difficulty_btns {
+			let difficulty = *difficulty;
+			let view = view.clone();
+			let quiz_view = quiz_view.clone();
+			btn.connect_clicked(move |_| {
+				view.app.settings.borrow_mut().num_choices = difficulty;
+				view.prefs.save();
+				view.mark(difficulty);
+
+				let progress = view.app.game.borrow_mut().take_progress();
+				let deck = view.app.current_deck.borrow();
+				let deck = deck.as_ref().expect("a topic is selected before the difficulty");
+				let mut config = view.app.settings.borrow().session_config(&deck.name);
+				config.speech = view.app.speech.borrow().is_some();
+				quiz_view.begin(Game::new(config, deck.meanings.clone(), progress));
+			});
+		}
+		{
+			let match_view = match_view.clone();
+			match_btn.connect_clicked(move |_| match_view.open());
+		}
+		{
+			let s = app.stack.clone();
+			back_btn_difficulty.connect_clicked(move |_| {
+				s.set_visible_child_name("topic_selection");
+			});
+		}
+		view
+	}
+
+	/// Sets up a round of `deck`, offering only the languages it has.
+	pub fn open(&self, deck: &Deck) {
+		self.app.current_deck.replace(Some(deck.clone()));
+		self.prefs.offer_languages(&self.app.languages.borrow(), Some(deck));
+		self.app.stack.set_visible_child_name("difficulty_selection");
+	}
+
+	/// Highlights the button of the difficulty last played.
+	pub fn mark(&self, num_choices: u32) {
+		for (n, btn) in &self.difficulty_btns {
+			if *n == num_choices {
+				btn.add_css_class("suggested-action");
+			} else {
+				btn.remove_css_class("suggested-action");
+			}
+		}
+	}
+}
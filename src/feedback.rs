@@ -0,0 +1,129 @@
+use corvid_core::highscores::HighScore;
+use corvid_core::language::Language;
+use corvid_core::matcher::Verdict;
+use corvid_core::matching::Board;
+use corvid_core::{Exercise, Game, Question, Summary};
+
+/// What to tell the learner after answering the current question with
+/// `chosen`: the verdict, the right answer next to theirs after a mistake,
+/// and whatever the card has to say about itself.
+pub fn result_text(game: &Game, chosen: &str, verdict: Verdict) -> String {
+	let Some(q) = game.current() else {
+		return String::new();
+	};
+	let mut lines = vec![match verdict {
+		Verdict::Correct => "Correct!".to_string(),
+		Verdict::NearMiss => format!("Almost! It is spelled “{}”.", q.answer),
+		Verdict::Regional => format!(
+			"Correct, but regional. In {} it is “{}”.",
+			Language::new(game.answer_lang()).name, q.answer,
+		),
+		Verdict::Wrong => "Wrong!".to_string(),
+	}];
+
+	if verdict == Verdict::Wrong {
+		lines.push(format!("You answered: {}", chosen.trim()));
+		lines.push(format!("Correct answer: {}", q.answer));
+	}
+	lines.extend(card_lines(game, q));
+	lines.join("\n")
+}
+
+/// What to tell the learner when the time for the current question ran out.
+pub fn timeout_text(game: &Game) -> String {
+	let Some(q) = game.current() else {
+		return String::new();
+	};
+	let mut lines = vec!["Time’s up!".to_string(), format!("Correct answer: {}", q.answer)];
+	lines.extend(card_lines(game, q));
+	lines.join("\n")
+}
+
+/// The other accepted answers and whatever the card has to say about itself.
+fn card_lines(game: &Game, q: &Question) -> Vec<String> {
+	let mut lines = Vec::new();
+	let others: Vec<&str> = q.accepted
+		.iter()
+		.filter(|a| **a != q.answer)
+		.map(String::as_str)
+		.collect();
+	if !others.is_empty() {
+		lines.push(format!("Also accepted: {}", others.join(", ")));
+	}
+
+	if game.exercise() == Exercise::Gender
+		&& let Some(word) = q.correct.word(game.target_lang())
+		&& let Some(gender) = word.gender
+	{
+		lines.push(format!("{} is {}.", word.text, gender.name()));
+	}
+	if let Some(note) = q.correct.note() {
+		lines.push(note.to_string());
+	}
+	lines
+}
+
+/// The lives left in a survival run, as full and empty hearts.
+pub fn lives_text(game: &Game) -> Option<String> {
+	let (lives, left) = (game.lives()?, game.lives_left()?);
+	let hearts: String = (0..lives).map(|i| if i < left { '♥' } else { '♡' }).collect();
+	Some(format!("Lives: {}", hearts))
+}
+
+/// The numbers of a finished session, one per line.
+pub fn summary_text(summary: &Summary) -> String {
+	let mut lines = vec![format!("Questions answered: {}", summary.answered)];
+	if let Some(points) = summary.points {
+		lines.push(format!("Points: {}", points));
+	}
+	if let Some(accuracy) = summary.accuracy() {
+		lines.push(format!("Accuracy: {:.0}% ({} right)", accuracy * 100.0, summary.correct));
+	}
+	if let Some(time) = summary.average_time {
+		lines.push(format!("Average answer time: {:.1} s", time.as_secs_f64()));
+	}
+	lines.push(format!("Longest streak: {}", summary.longest_streak));
+	lines.join("\n")
+}
+
+/// The clock and mismatch count of a matching board, or how it went once
+/// every pair is matched.
+pub fn match_status(board: &Board) -> String {
+	let secs = board.elapsed().as_secs();
+	let time = format!("{}:{:02}", secs / 60, secs % 60);
+	if board.is_complete() {
+		let mismatches = match board.mismatches() {
+			0 => "no mismatches".to_string(),
+			1 => "1 mismatch".to_string(),
+			n => format!("{} mismatches", n),
+		};
+		format!("All {} pairs matched in {} with {}.", board.pairs(), time, mismatches)
+	} else {
+		format!("Time: {}   Mismatches: {}", time, board.mismatches())
+	}
+}
+
+/// A missed question and its right answer, for the list of missed words.
+/// Listening questions present the answer itself, so it is given once.
+pub fn missed_text(question: &Question) -> String {
+	if question.presented_word == question.answer {
+		return question.answer.clone();
+	}
+	format!("{} — {}", question.presented_word, question.answer)
+}
+
+/// A high-score table, best first, with the round at `place` marked.
+pub fn highscore_text(title: &str, table: &[HighScore], place: Option<usize>) -> String {
+	let mut lines = vec![title.to_string()];
+	for (i, score) in table.iter().enumerate() {
+		let mark = if Some(i) == place { "  ← new" } else { "" };
+		lines.push(format!(
+			"{:>2}. {}  {} points ({}/{}){}",
+			i + 1, score.name, score.points, score.correct, score.answered, mark,
+		));
+	}
+	if place.is_none() {
+		lines.push("This round did not make the table.".to_string());
+	}
+	lines.join("\n")
+}
@@ -0,0 +1,309 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, CheckButton, ComboBoxText, Grid, Label, Orientation, PolicyType, ScrolledWindow};
+use std::{cell::RefCell, path::Path, rc::Rc};
+
+use corvid_core::anki::{Export, Field, Imported};
+use corvid_core::language;
+use corvid_core::storage::Progress;
+
+use crate::prefs::Prefs;
+use crate::topics_view::TopicsView;
+use crate::{App, paths, save_progress};
+
+/// The Anki import page: what each note field becomes, and whether the
+/// review history comes along.
+#[derive(Clone)]
+pub struct ImportView {
+	app: App,
+	prefs: Prefs,
+	topics_view: TopicsView,
+	title_label: Label,
+	grid: Grid,
+	/// One per field of the export, with ids "skip", "note", "picture" or
+	/// "lang:<code>".
+	field_combos: Rc<RefCell<Vec<ComboBoxText>>>,
+	history_check: CheckButton,
+	known_combo: ComboBoxText,
+	learning_combo: ComboBoxText,
+	status_label: Label,
+	import_btn: Button,
+	export: Rc<RefCell<Option<Export>>>,
+	/// Languages offered for fields: every one Corvid has a name for, and
+	/// any other one a deck uses.
+	languages: Rc<Vec<(String, String)>>,
+	/// Kept while the file chooser is open.
+	chooser: Rc<RefCell<Option<gtk::FileChooserNative>>>,
+}
+
+impl ImportView {
+	/// Builds the import page. Imported decks are offered in `prefs` and
+	/// listed in `topics_view`.
+	pub fn new(app: &App, prefs: &Prefs, topics_view: &TopicsView) -> Self {
+		let back_btn_import = Button::with_label("Back");
+		let import_title_label = Label::new(None);
+		let import_grid = Grid::builder()
+			.row_spacing(8)
+			.column_spacing(12)
+			.margin_start(12)
+			.margin_end(12)
+			.build();
+		let import_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.vexpand(true)
+			.child(&import_grid)
+			.build();
+		let history_check = CheckButton::builder().margin_start(12).build();
+		let known_combo = ComboBoxText::new();
+		let learning_combo = ComboBoxText::new();
+		let history_row = GtkBox::builder()
+			.orientation(Orientation::Horizontal)
+			.spacing(8)
+			.margin_start(12)
+			.margin_end(12)
+			.sensitive(false)
+			.build();
+		history_row.append(&Label::new(Some("as words in")));
+		history_row.append(&learning_combo);
+		history_row.append(&Label::new(Some("answered in")));
+		history_row.append(&known_combo);
+		{
+			let row = history_row.clone();
+			history_check.connect_toggled(move |check| row.set_sensitive(check.is_active()));
+		}
+		let import_status_label = Label::builder().wrap(true).margin_start(12).margin_end(12).build();
+		let import_btn = Button::with_label("Import");
+		for btn in &[&back_btn_import, &import_btn] {
+			btn.set_margin_top(12);
+			btn.set_margin_bottom(12);
+			btn.set_margin_start(12);
+			btn.set_margin_end(12);
+		}
+		let import_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.build();
+		import_box.append(&back_btn_import);
+		import_box.append(&import_title_label);
+		import_box.append(&import_scroll);
+		import_box.append(&history_check);
+		import_box.append(&history_row);
+		import_box.append(&import_btn);
+		import_box.append(&import_status_label);
+		app.stack.add_named(&import_box, Some("anki_import"));
+
+		let mut import_languages: Vec<(String, String)> =
+			language::known().into_iter().map(|l| (l.code, l.name)).collect();
+		for l in app.languages.borrow().all() {
+			if !import_languages.iter().any(|(code, _)| *code == l.code) {
+				import_languages.push((l.code.clone(), l.name.clone()));
+			}
+		}
+		import_languages.sort_by(|a, b| a.1.cmp(&b.1));
+		let view = ImportView {
+			app: app.clone(),
+			prefs: prefs.clone(),
+			topics_view: topics_view.clone(),
+			title_label: import_title_label,
+			grid: import_grid,
+			field_combos: Rc::new(RefCell::new(Vec::new())),
+			history_check,
+			known_combo,
+			learning_combo,
+			status_label: import_status_label,
+			import_btn: import_btn.clone(),
+			export: Rc::new(RefCell::new(None)),
+			languages: Rc::new(import_languages),
+			chooser: Rc::new(RefCell::new(None)),
+		};
+		{
+			let s = app.stack.clone();
+			back_btn_import.connect_clicked(move |_| s.set_visible_child_name("main_menu"));
+		}
+		{
+			let view = view.clone();
+			import_btn.connect_clicked(move |_| view.add_deck());
+		}
+		view
+	}
+
+	/// Asks for an Anki package or text export and opens it on the page.
+	pub fn choose_file(&self) {
+		let chooser = gtk::FileChooserNative::new(
+			Some("Import from Anki"),
+			Some(&self.app.window),
+			gtk::FileChooserAction::Open,
+			Some("_Open"),
+			Some("_Cancel"),
+		);
+		let filter = gtk::FileFilter::new();
+		filter.set_name(Some("Anki packages and text exports"));
+		for pattern in ["*.apkg", "*.txt", "*.tsv", "*.csv"] {
+			filter.add_pattern(pattern);
+		}
+		chooser.add_filter(&filter);
+		let view = self.clone();
+		chooser.connect_response(move |chooser, response| {
+			if response == gtk::ResponseType::Accept
+				&& let Some(path) = chooser.file().and_then(|f| f.path())
+			{
+				view.open(&path);
+				view.app.stack.set_visible_child_name("anki_import");
+			}
+			view.chooser.replace(None);
+		});
+		chooser.show();
+		self.chooser.replace(Some(chooser));
+	}
+
+	/// Imports the open export into the learner's decks and progress.
+	fn add_deck(&self) {
+		let imported = {
+			let mut g = self.app.game.borrow_mut();
+			let mut progress = g.take_progress();
+			let imported = self.import(&mut progress);
+			g.replace_progress(progress);
+			if imported.is_some() {
+				save_progress(g.progress(), &self.app.profiles.borrow());
+			}
+			imported
+		};
+		// The new deck can be practised straight away.
+		let Some(imported) = imported else { return };
+		let deck = imported.deck;
+		self.app.languages.borrow_mut().add_deck(&deck);
+		self.prefs.add_deck(&deck, &self.app.languages.borrow());
+		self.app.decks.borrow_mut().push(deck.clone());
+		self.topics_view.add_deck(&deck);
+	}
+
+	/// Reads the export at `path` and lists its fields, each set to a guess
+	/// at what it holds.
+	fn open(&self, path: &Path) {
+		while let Some(child) = self.grid.first_child() {
+			self.grid.remove(&child);
+		}
+		self.field_combos.borrow_mut().clear();
+		let export = match Export::read(path) {
+			Ok(export) => export,
+			Err(e) => {
+				self.title_label.set_text("This file cannot be imported.");
+				self.status_label.set_text(&e.to_string());
+				self.import_btn.set_sensitive(false);
+				self.export.replace(None);
+				return;
+			}
+		};
+		let reviews: usize = export.notes.iter().map(|n| n.reviews.len()).sum();
+		self.title_label.set_text(&format!("{}: {} notes", export.name, export.notes.len()));
+		self.history_check.set_label(Some(&format!("Bring over the review history ({} reviews)", reviews)));
+		self.history_check.set_active(reviews > 0);
+		self.history_check.set_sensitive(reviews > 0);
+		self.status_label.set_text("Choose what each field holds.");
+		self.import_btn.set_sensitive(true);
+
+		for (i, (name, guess)) in export.fields.iter().zip(export.guess_fields()).enumerate() {
+			let combo = ComboBoxText::new();
+			combo.append(Some("skip"), "Leave out");
+			combo.append(Some("note"), "Note");
+			combo.append(Some("picture"), "Picture");
+			for (code, name) in self.languages.iter() {
+				combo.append(Some(&format!("lang:{}", code)), name);
+			}
+			combo.set_active_id(Some(&match guess {
+				Field::Skip => "skip".to_string(),
+				Field::Note => "note".to_string(),
+				Field::Picture => "picture".to_string(),
+				Field::Language(code) => format!("lang:{}", code),
+			}));
+			let view = self.clone();
+			combo.connect_changed(move |_| view.refill_history_combos());
+			let sample = Label::builder()
+				.label(export.sample(i))
+				.ellipsize(gtk::pango::EllipsizeMode::End)
+				.max_width_chars(24)
+				.xalign(0.0)
+				.build();
+			self.grid.attach(&Label::builder().label(name).xalign(0.0).build(), 0, i as i32, 1, 1);
+			self.grid.attach(&combo, 1, i as i32, 1, 1);
+			self.grid.attach(&sample, 2, i as i32, 1, 1);
+			self.field_combos.borrow_mut().push(combo);
+		}
+		self.export.replace(Some(export));
+		self.refill_history_combos();
+	}
+
+	/// What each field becomes, as chosen.
+	fn fields(&self) -> Vec<Field> {
+		self.field_combos
+			.borrow()
+			.iter()
+			.map(|combo| match combo.active_id().as_deref() {
+				Some("note") => Field::Note,
+				Some("picture") => Field::Picture,
+				Some(id) if id.starts_with("lang:") => Field::Language(id["lang:".len()..].to_string()),
+				_ => Field::Skip,
+			})
+			.collect()
+	}
+
+	/// Offers the languages given to fields for the review history, which
+	/// counts as answers in the known language to words in the learned one.
+	fn refill_history_combos(&self) {
+		let codes: Vec<String> = self
+			.fields()
+			.into_iter()
+			.filter_map(|f| match f {
+				Field::Language(code) => Some(code),
+				_ => None,
+			})
+			.collect();
+		for (combo, fallback) in [(&self.known_combo, 0), (&self.learning_combo, 1)] {
+			let active = combo.active_id();
+			combo.remove_all();
+			for code in &codes {
+				let name = self.languages.iter().find(|(c, _)| c == code).map_or(code.as_str(), |(_, n)| n);
+				combo.append(Some(code), name);
+			}
+			let keep = active.is_some_and(|id| combo.set_active_id(Some(&id)));
+			if !keep {
+				combo.set_active(Some(fallback.min(codes.len().saturating_sub(1)) as u32));
+			}
+		}
+	}
+
+	/// Writes the deck into the user's deck directory and, if asked,
+	/// replays the review history into `progress`. Reports the outcome on
+	/// the page.
+	fn import(&self, progress: &mut Progress) -> Option<Imported> {
+		let export = self.export.borrow();
+		let export = export.as_ref()?;
+		let imported = match export.import(&self.fields(), &paths::data_dir().join("decks")) {
+			Ok(imported) => imported,
+			Err(e) => {
+				self.status_label.set_text(&e.to_string());
+				return None;
+			}
+		};
+		let mut report = format!(
+			"Imported {} meanings into {}.",
+			imported.deck.meanings.len(),
+			imported.deck.path.display(),
+		);
+		if imported.skipped > 0 {
+			report.push_str(&format!("\n{} notes had no words or repeated another.", imported.skipped));
+		}
+		if imported.missing_media > 0 {
+			report.push_str(&format!("\n{} recordings or images were missing.", imported.missing_media));
+		}
+		if self.history_check.is_active()
+			&& let (Some(known), Some(learning)) = (self.known_combo.active_id(), self.learning_combo.active_id())
+			&& known != learning
+		{
+			let replayed = imported.import_history(progress, &known, &learning);
+			report.push_str(&format!("\n{} of {} reviews were brought over.", replayed, imported.review_count()));
+		}
+		self.status_label.set_text(&report);
+		self.import_btn.set_sensitive(false);
+		Some(imported)
+	}
+}
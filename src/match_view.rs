@@ -0,0 +1,198 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, Grid, Label, Orientation, PolicyType, ScrolledWindow};
+use gtk::glib;
+use std::{cell::{Cell, RefCell}, rc::Rc, time::Duration};
+
+use corvid_core::matching::{self, Board, Pick};
+
+use crate::prefs::Prefs;
+use crate::{App, feedback, save_progress};
+
+/// The matching-pairs page. While a board is dealt it holds the learner's
+/// progress, which goes back to the game when the page is left.
+#[derive(Clone)]
+pub struct MatchView {
+	app: App,
+	prefs: Prefs,
+	grid: Grid,
+	status_label: Label,
+	new_board_btn: Button,
+	board: Rc<RefCell<Option<Board>>>,
+	tiles: Rc<RefCell<Vec<Button>>>,
+	/// Bumped whenever a board is dealt or left, so that the clock of an
+	/// earlier board stops.
+	serial: Rc<Cell<u32>>,
+}
+
+impl MatchView {
+	pub fn new(app: &App, prefs: &Prefs) -> Self {
+		let back_btn_match = Button::with_label("Back");
+		let new_board_btn = Button::builder().label("New board").visible(false).build();
+		for btn in &[&back_btn_match, &new_board_btn] {
+			btn.set_margin_top(12);
+			btn.set_margin_bottom(12);
+			btn.set_margin_start(12);
+			btn.set_margin_end(12);
+		}
+		let match_status_label = Label::new(None);
+		let match_grid = Grid::builder()
+			.row_spacing(8)
+			.column_spacing(16)
+			.margin_start(12)
+			.margin_end(12)
+			.build();
+		let match_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.vexpand(true)
+			.child(&match_grid)
+			.build();
+		let match_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.build();
+		match_box.append(&back_btn_match);
+		match_box.append(&match_status_label);
+		match_box.append(&match_scroll);
+		match_box.append(&new_board_btn);
+		app.stack.add_named(&match_box, Some("matching"));
+
+		let view = MatchView {
+			app: app.clone(),
+			prefs: prefs.clone(),
+			grid: match_grid,
+			status_label: match_status_label,
+			new_board_btn: new_board_btn.clone(),
+			board: Rc::new(RefCell::new(None)),
+			tiles: Rc::new(RefCell::new(Vec::new())),
+			serial: Rc::new(Cell::new(0)),
+		};
+		{
+			let view = view.clone();
+			new_board_btn.connect_clicked(move |_| view.open());
+		}
+		{
+			let view = view.clone();
+			back_btn_match.connect_clicked(move |_| {
+				view.leave();
+				view.app.stack.set_visible_child_name("difficulty_selection");
+			});
+		}
+		view
+	}
+
+	/// Deals a board of the selected topic in the learner's languages,
+	/// taking the progress out of the game, and shows it.
+	pub fn open(&self) {
+		self.prefs.save();
+		self.leave();
+		let progress = self.app.game.borrow_mut().take_progress();
+		let board = {
+			let deck = self.app.current_deck.borrow();
+			let deck = deck.as_ref().expect("a topic is selected before the board");
+			let settings = self.app.settings.borrow();
+			Board::new(
+				&deck.name,
+				&deck.meanings,
+				&settings.source_lang,
+				&settings.target_lang,
+				matching::DEFAULT_PAIRS,
+				progress,
+			)
+		};
+
+		while let Some(child) = self.grid.first_child() {
+			self.grid.remove(&child);
+		}
+		let mut tiles = Vec::new();
+		if board.pairs() == 0 {
+			let label = Label::new(Some("This topic has too few words in these languages for a board."));
+			self.grid.attach(&label, 0, 0, 2, 1);
+		} else {
+			self.grid.attach(&Label::new(Some(&self.app.languages.borrow().name(board.source_lang()))), 0, 0, 1, 1);
+			self.grid.attach(&Label::new(Some(&self.app.languages.borrow().name(board.target_lang()))), 1, 0, 1, 1);
+			for (i, tile) in board.tiles().iter().enumerate() {
+				let button = Button::builder().label(&tile.text).hexpand(true).build();
+				// Known words on the left, their translations on the right.
+				let (column, row) = if i < board.pairs() { (0, i) } else { (1, i - board.pairs()) };
+				self.grid.attach(&button, column, row as i32 + 1, 1, 1);
+				let view = self.clone();
+				button.connect_clicked(move |_| view.pick(i));
+				tiles.push(button);
+			}
+		}
+		self.tiles.replace(tiles);
+		self.board.replace(Some(board));
+		self.refresh();
+
+		let serial = self.serial.get();
+		let view = self.clone();
+		glib::timeout_add_local(Duration::from_millis(250), move || {
+			if view.serial.get() != serial {
+				return glib::ControlFlow::Break;
+			}
+			let board = view.board.borrow();
+			let Some(board) = board.as_ref().filter(|b| b.pairs() > 0) else {
+				return glib::ControlFlow::Break;
+			};
+			view.status_label.set_text(&feedback::match_status(board));
+			if board.is_complete() {
+				glib::ControlFlow::Break
+			} else {
+				glib::ControlFlow::Continue
+			}
+		});
+		self.app.stack.set_visible_child_name("matching");
+	}
+
+	fn pick(&self, index: usize) {
+		let picked = {
+			let mut board = self.board.borrow_mut();
+			let Some(board) = board.as_mut() else { return };
+			board.pick(index)
+		};
+		if let Pick::Matched(..) | Pick::Mismatched(..) = picked
+			&& let Some(board) = self.board.borrow().as_ref()
+		{
+			save_progress(board.progress(), &self.app.profiles.borrow());
+		}
+		// Flash the two tiles that do not belong together.
+		if let Pick::Mismatched(a, b) = picked {
+			let tiles = self.tiles.borrow();
+			let flashed = [tiles[a].clone(), tiles[b].clone()];
+			for tile in &flashed {
+				tile.add_css_class("destructive-action");
+			}
+			glib::timeout_add_local_once(Duration::from_millis(600), move || {
+				for tile in &flashed {
+					tile.remove_css_class("destructive-action");
+				}
+			});
+		}
+		self.refresh();
+	}
+
+	/// Shows which tiles are selected and matched, and the board's status.
+	fn refresh(&self) {
+		let board = self.board.borrow();
+		let Some(board) = board.as_ref() else { return };
+		for (i, (button, tile)) in self.tiles.borrow().iter().zip(board.tiles()).enumerate() {
+			button.set_sensitive(!tile.matched);
+			if board.selected() == Some(i) {
+				button.add_css_class("suggested-action");
+			} else {
+				button.remove_css_class("suggested-action");
+			}
+		}
+		self.status_label.set_visible(board.pairs() > 0);
+		self.status_label.set_text(&feedback::match_status(board));
+		self.new_board_btn.set_visible(board.is_complete());
+	}
+
+	/// Stops the clock and hands the learner's progress back to the game.
+	fn leave(&self) {
+		self.serial.set(self.serial.get().wrapping_add(1));
+		if let Some(mut board) = self.board.take() {
+			self.app.game.borrow_mut().replace_progress(board.take_progress());
+		}
+	}
+}
@@ -0,0 +1,233 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, ComboBoxText, Entry, Label, Orientation};
+use std::rc::Rc;
+
+use corvid_core::profile::Profiles;
+use corvid_core::Game;
+
+use crate::difficulty_view::DifficultyView;
+use crate::import_view::ImportView;
+use crate::prefs::Prefs;
+use crate::stats_view::StatsView;
+use crate::{App, paths};
+
+fn fill_profile_combo(combo: &ComboBoxText, profiles: &Profiles) {
+	combo.remove_all();
+	for profile in profiles.all() {
+		combo.append(Some(&profile.id), &profile.name);
+	}
+	combo.set_active_id(Some(&profiles.active().id));
+}
+
+/// Builds the main menu: who is learning, and the way to every other page.
+pub fn build(app: &App, prefs: &Prefs, difficulty_view: &DifficultyView, stats_view: &StatsView, import_view: &ImportView) {
+	let profiles = &app.profiles;
+	let profile_combo = ComboBoxText::new();
+	fill_profile_combo(&profile_combo, &profiles.borrow());
+	let profile_name_entry = Entry::builder()
+		.placeholder_text("Profile name")
+		.hexpand(true)
+		.build();
+	let new_profile_btn = Button::with_label("New");
+	let rename_profile_btn = Button::with_label("Rename");
+	let delete_profile_btn = Button::with_label("Delete");
+	let profile_status = Label::builder().visible(false).build();
+	let profile_actions = GtkBox::builder()
+		.orientation(Orientation::Horizontal)
+		.spacing(8)
+		.build();
+	profile_actions.append(&profile_name_entry);
+	profile_actions.append(&new_profile_btn);
+	profile_actions.append(&rename_profile_btn);
+	profile_actions.append(&delete_profile_btn);
+	let profile_box = GtkBox::builder()
+		.orientation(Orientation::Vertical)
+		.spacing(8)
+		.margin_top(12)
+		.margin_start(12)
+		.margin_end(12)
+		.build();
+	profile_box.append(&Label::new(Some("Who is learning:")));
+	profile_box.append(&profile_combo);
+	profile_box.append(&profile_actions);
+	profile_box.append(&profile_status);
+
+	let open_vocab_btn = Button::with_label("Vocabulary");
+	let prefs_btn = Button::with_label("Preferences");
+	let stats_btn = Button::with_label("Statistics");
+	let import_anki_btn = Button::with_label("Import from Anki");
+	for btn in &[&open_vocab_btn, &prefs_btn, &stats_btn, &import_anki_btn] {
+		btn.set_margin_top(12);
+		btn.set_margin_bottom(12);
+		btn.set_margin_start(12);
+		btn.set_margin_end(12);
+	}
+	let main_menu = GtkBox::builder()
+		.orientation(Orientation::Vertical)
+		.spacing(8)
+		.build();
+	main_menu.append(&profile_box);
+	main_menu.append(&open_vocab_btn);
+	main_menu.append(&prefs_btn);
+	main_menu.append(&stats_btn);
+	main_menu.append(&import_anki_btn);
+	app.stack.add_named(&main_menu, Some("main_menu"));
+
+	{
+		let s = app.stack.clone();
+		let settings = app.settings.clone();
+		let decks = app.decks.clone();
+		let difficulty_view = difficulty_view.clone();
+		open_vocab_btn.connect_clicked(move |_| {
+			let default_deck = settings.borrow().default_deck.clone();
+			match decks.borrow().iter().find(|d| Some(&d.name) == default_deck.as_ref()) {
+				Some(deck) => difficulty_view.open(deck),
+				None => s.set_visible_child_name("topic_selection"),
+			}
+		});
+	}
+	{
+		let s = app.stack.clone();
+		prefs_btn.connect_clicked(move |_| {
+			s.set_visible_child_name("preferences");
+		});
+	}
+	{
+		let stats_view = stats_view.clone();
+		stats_btn.connect_clicked(move |_| stats_view.show());
+	}
+	{
+		let import_view = import_view.clone();
+		import_anki_btn.connect_clicked(move |_| import_view.choose_file());
+	}
+
+	// Switches to another learner, saving the current one's preferences
+	// first unless the profile is being deleted.
+	let switch_profile = {
+		let profiles = profiles.clone();
+		let settings = app.settings.clone();
+		let prefs = prefs.clone();
+		let game = app.game.clone();
+		let languages = app.languages.clone();
+		let difficulty_view = difficulty_view.clone();
+		Rc::new(move |id: &str, save_current: bool| {
+			if save_current {
+				prefs.save();
+			}
+			if let Err(e) = profiles.borrow_mut().set_active(id) {
+				eprintln!("corvid: {}", e);
+				return;
+			}
+			if let Err(e) = profiles.borrow().save(&paths::profiles_file()) {
+				eprintln!("corvid: cannot save profiles: {}", e);
+			}
+			settings.replace(paths::load_settings(id));
+			prefs.apply(&languages.borrow());
+			difficulty_view.mark(settings.borrow().num_choices);
+			game.replace(Game::idle(paths::load_progress(id)));
+		})
+	};
+
+	{
+		let profiles = profiles.clone();
+		let switch_profile = switch_profile.clone();
+		profile_combo.connect_changed(move |combo| {
+			let Some(id) = combo.active_id() else { return };
+			if id != profiles.borrow().active().id {
+				switch_profile(&id, true);
+			}
+		});
+	}
+
+	{
+		let profiles = profiles.clone();
+		let combo = profile_combo.clone();
+		let entry = profile_name_entry.clone();
+		let status = profile_status.clone();
+		new_profile_btn.connect_clicked(move |_| {
+			let created = profiles.borrow_mut().create(&entry.text());
+			match created {
+				Ok(id) => {
+					status.set_visible(false);
+					entry.set_text("");
+					fill_profile_combo(&combo, &profiles.borrow());
+					combo.set_active_id(Some(&id));
+				}
+				Err(e) => {
+					status.set_text(&e.to_string());
+					status.set_visible(true);
+				}
+			}
+		});
+	}
+
+	{
+		let profiles = profiles.clone();
+		let combo = profile_combo.clone();
+		let entry = profile_name_entry.clone();
+		let status = profile_status.clone();
+		rename_profile_btn.connect_clicked(move |_| {
+			let id = profiles.borrow().active().id.clone();
+			let renamed = profiles.borrow_mut().rename(&id, &entry.text());
+			match renamed {
+				Ok(()) => {
+					status.set_visible(false);
+					entry.set_text("");
+					if let Err(e) = profiles.borrow().save(&paths::profiles_file()) {
+						eprintln!("corvid: cannot save profiles: {}", e);
+					}
+					fill_profile_combo(&combo, &profiles.borrow());
+				}
+				Err(e) => {
+					status.set_text(&e.to_string());
+					status.set_visible(true);
+				}
+			}
+		});
+	}
+
+	{
+		let profiles = profiles.clone();
+		let combo = profile_combo.clone();
+		let status = profile_status.clone();
+		let window = app.window.clone();
+		delete_profile_btn.connect_clicked(move |_| {
+			if profiles.borrow().all().len() == 1 {
+				status.set_text("The last profile cannot be deleted.");
+				status.set_visible(true);
+				return;
+			}
+			status.set_visible(false);
+			let active = profiles.borrow().active().clone();
+			let dialog = gtk::MessageDialog::builder()
+				.transient_for(&window)
+				.modal(true)
+				.message_type(gtk::MessageType::Question)
+				.buttons(gtk::ButtonsType::OkCancel)
+				.text(format!("Delete {}?", active.name))
+				.secondary_text("Their progress and preferences are deleted too.")
+				.build();
+			let profiles = profiles.clone();
+			let combo = combo.clone();
+			let switch_profile = switch_profile.clone();
+			dialog.connect_response(move |dialog, response| {
+				dialog.destroy();
+				if response != gtk::ResponseType::Ok {
+					return;
+				}
+				let removed = profiles.borrow_mut().remove(&active.id);
+				if let Err(e) = removed {
+					eprintln!("corvid: {}", e);
+					return;
+				}
+				if let Err(e) = paths::remove_profile_files(&active.id) {
+					eprintln!("corvid: cannot delete the files of {}: {}", active.name, e);
+				}
+				let next = profiles.borrow().active().id.clone();
+				switch_profile(&next, false);
+				fill_profile_combo(&combo, &profiles.borrow());
+			});
+			dialog.show();
+		});
+	}
+}
@@ -0,0 +1,140 @@
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+use corvid_core::highscores::HighScores;
+use corvid_core::persist::LoadError;
+use corvid_core::profile::Profiles;
+use corvid_core::settings::Settings;
+use corvid_core::storage::Progress;
+
+/// `$XDG_DATA_HOME/corvid`, falling back to `~/.local/share/corvid`.
+pub fn data_dir() -> PathBuf {
+	xdg_dir("XDG_DATA_HOME", ".local/share").join("corvid")
+}
+
+/// `$XDG_CONFIG_HOME/corvid`, falling back to `~/.config/corvid`.
+pub fn config_dir() -> PathBuf {
+	xdg_dir("XDG_CONFIG_HOME", ".config").join("corvid")
+}
+
+fn xdg_dir(var: &str, fallback: &str) -> PathBuf {
+	match env::var_os(var) {
+		Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+		_ => env::var_os("HOME")
+			.map(PathBuf::from)
+			.unwrap_or_default()
+			.join(fallback),
+	}
+}
+
+/// Lightning high scores, shared by every profile.
+pub fn highscores_file() -> PathBuf {
+	data_dir().join("highscores.json")
+}
+
+pub fn profiles_file() -> PathBuf {
+	config_dir().join("profiles.toml")
+}
+
+pub fn progress_file(profile: &str) -> PathBuf {
+	data_dir().join("profiles").join(profile).join("progress.json")
+}
+
+pub fn settings_file(profile: &str) -> PathBuf {
+	config_dir().join("profiles").join(profile).join("settings.toml")
+}
+
+/// Loads the profile list. The first time, progress and settings saved
+/// before there were profiles become the default profile's.
+pub fn open_profiles() -> Profiles {
+	let path = profiles_file();
+	if path.exists() {
+		return or_default(Profiles::load(&path));
+	}
+	let profiles = Profiles::default();
+	let id = &profiles.active().id;
+	let legacy = [
+		(data_dir().join("progress.json"), progress_file(id)),
+		(config_dir().join("settings.toml"), settings_file(id)),
+	];
+	for (from, to) in legacy {
+		if from.exists() && !to.exists() {
+			let moved = to.parent().map_or(Ok(()), fs::create_dir_all).and_then(|()| fs::rename(&from, &to));
+			if let Err(e) = moved {
+				eprintln!("corvid: cannot move {} to {}: {}", from.display(), to.display(), e);
+			}
+		}
+	}
+	if let Err(e) = profiles.save(&path) {
+		eprintln!("corvid: cannot save profiles: {}", e);
+	}
+	profiles
+}
+
+pub fn load_progress(profile: &str) -> Progress {
+	or_default(Progress::load(&progress_file(profile)))
+}
+
+pub fn load_settings(profile: &str) -> Settings {
+	or_default(Settings::load(&settings_file(profile)))
+}
+
+pub fn load_highscores() -> HighScores {
+	or_default(HighScores::load(&highscores_file()))
+}
+
+/// What was loaded, or the defaults when the file could not be used, in
+/// which case the learner hears why.
+fn or_default<T: Default>(loaded: Result<T, LoadError>) -> T {
+	loaded.unwrap_or_else(|e| {
+		eprintln!("corvid: {}", e);
+		T::default()
+	})
+}
+
+/// Deletes everything saved for a profile.
+pub fn remove_profile_files(profile: &str) -> io::Result<()> {
+	// Ids are made of letters, digits and dashes; anything else came from a
+	// hand-edited file and could point outside the profile directories.
+	if profile.is_empty() || !profile.chars().all(|c| c.is_alphanumeric() || c == '-') {
+		return Err(io::Error::other(format!("invalid profile id {:?}", profile)));
+	}
+	for file in [progress_file(profile), settings_file(profile)] {
+		if let Some(dir) = file.parent()
+			&& dir.exists()
+		{
+			fs::remove_dir_all(dir)?;
+		}
+	}
+	Ok(())
+}
+
+/// Directories searched for deck files, in load order. Decks found in a
+/// later directory replace decks with the same file name in an earlier one,
+/// so the learner's own directory comes last.
+pub fn deck_dirs() -> Vec<PathBuf> {
+	let mut dirs = bundled_deck_dirs();
+	dirs.push(data_dir().join("decks"));
+	dirs
+}
+
+/// Where the decks shipped with Corvid may be installed, least preferred
+/// first: `corvid/decks` in each of `$XDG_DATA_DIRS`, then beside the
+/// executable, as in an unpacked archive, or in the `share` directory of
+/// its install prefix. Debug builds also read the source tree's decks.
+fn bundled_deck_dirs() -> Vec<PathBuf> {
+	let mut dirs = Vec::new();
+	if cfg!(debug_assertions) {
+		dirs.push(PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/decks")));
+	}
+	let data_dirs = env::var_os("XDG_DATA_DIRS")
+		.filter(|dirs| !dirs.is_empty())
+		.unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+	let data_dirs: Vec<PathBuf> = env::split_paths(&data_dirs).collect();
+	dirs.extend(data_dirs.into_iter().rev().map(|dir| dir.join("corvid").join("decks")));
+	if let Some(bin) = env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+		dirs.push(bin.join("../share/corvid/decks"));
+		dirs.push(bin.join("decks"));
+	}
+	dirs
+}
@@ -0,0 +1,405 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, CheckButton, ComboBoxText, Entry, Label, Orientation};
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+use corvid_core::deck::Deck;
+use corvid_core::language::Languages;
+use corvid_core::matcher::Tolerance;
+use corvid_core::profile::Profiles;
+use corvid_core::settings::{self, Challenge, Settings, Voice};
+use corvid_core::{Exercise, QuizMode, RetryPolicy};
+
+use crate::{App, paths};
+
+/// The widgets showing the active learner's settings, spread over the
+/// preferences and difficulty pages.
+#[derive(Clone)]
+pub struct Prefs {
+	settings: Rc<RefCell<Settings>>,
+	profiles: Rc<RefCell<Profiles>>,
+	source_lang: ComboBoxText,
+	target_lang: ComboBoxText,
+	direction: ComboBoxText,
+	exercise: ComboBoxText,
+	mode: ComboBoxText,
+	retry: ComboBoxText,
+	ignore_accents: CheckButton,
+	optional_articles: CheckButton,
+	allow_typos: CheckButton,
+	show_romanization: CheckButton,
+	default_deck: ComboBoxText,
+	session_length: ComboBoxText,
+	challenge: ComboBoxText,
+	time_limit: ComboBoxText,
+	speak_prompts: CheckButton,
+	voice_name: Entry,
+	speech_rate: ComboBoxText,
+	/// The voices being edited, by language.
+	voices: Rc<RefCell<BTreeMap<String, Voice>>>,
+	/// The language the voice widgets show.
+	voice_lang: Rc<RefCell<Option<String>>>,
+}
+
+impl Prefs {
+	/// Builds the preferences page, and the round settings the difficulty
+	/// page lays out, set from the stored settings.
+	pub fn new(app: &App) -> Self {
+		let back_btn_prefs = Button::with_label("Back");
+		let source_lang_combo = ComboBoxText::new();
+		let target_lang_combo = ComboBoxText::new();
+
+		fill_language_combos(&source_lang_combo, &target_lang_combo, &app.languages.borrow(), None);
+		{
+			let target_combo = target_lang_combo.clone();
+			let languages = app.languages.clone();
+			let current_deck = app.current_deck.clone();
+			source_lang_combo.connect_changed(move |source_combo| {
+				fill_target_combo(source_combo, &target_combo, &languages.borrow(), current_deck.borrow().as_ref());
+			});
+		}
+
+		let ignore_accents_check = CheckButton::with_label("Ignore accents when typing");
+		let optional_articles_check = CheckButton::with_label("Articles are optional when typing");
+		let allow_typos_check = CheckButton::with_label("Accept small typos when typing");
+		let show_romanization_check = CheckButton::with_label("Show romanization under the word");
+
+		let speak_prompts_check = CheckButton::with_label("Read prompts aloud");
+		let voice_name_entry = Entry::builder().placeholder_text("The speech engine’s choice").build();
+		let speech_rate_combo = ComboBoxText::new();
+		for (id, name) in [("0.75", "Slow"), ("1", "Normal"), ("1.25", "Fast")] {
+			speech_rate_combo.append(Some(id), name);
+		}
+		if app.speech.borrow().is_none() {
+			speak_prompts_check.set_label(Some("Read prompts aloud (needs speech-dispatcher or espeak-ng)"));
+			for widget in [speak_prompts_check.upcast_ref::<gtk::Widget>(), voice_name_entry.upcast_ref(), speech_rate_combo.upcast_ref()] {
+				widget.set_sensitive(false);
+			}
+		}
+
+		let retry_combo = ComboBoxText::new();
+		retry_combo.append(Some("now"), "Ask it again right away");
+		retry_combo.append(Some("later"), "Ask it again a little later");
+		retry_combo.append(Some("move_on"), "Move on");
+
+		let default_deck_combo = ComboBoxText::new();
+		default_deck_combo.append(Some(""), "Choose each time");
+		for d in app.decks.borrow().iter() {
+			default_deck_combo.append(Some(&d.name), &d.name);
+		}
+		let session_length_combo = ComboBoxText::new();
+		session_length_combo.append(Some("0"), "Until I stop");
+		for n in ["10", "20", "50"] {
+			session_length_combo.append(Some(n), n);
+		}
+		let time_limit_combo = ComboBoxText::new();
+		for secs in ["3", "5", "10", "20"] {
+			time_limit_combo.append(Some(secs), &format!("{} seconds", secs));
+		}
+
+		let prefs_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.margin_top(12)
+			.margin_bottom(12)
+			.margin_start(12)
+			.margin_end(12)
+			.build();
+
+		prefs_box.append(&Label::new(Some("Known Language:")));
+		prefs_box.append(&source_lang_combo);
+		prefs_box.append(&Label::new(Some("Learning Language:")));
+		prefs_box.append(&target_lang_combo);
+		prefs_box.append(&ignore_accents_check);
+		prefs_box.append(&optional_articles_check);
+		prefs_box.append(&allow_typos_check);
+		prefs_box.append(&show_romanization_check);
+		prefs_box.append(&speak_prompts_check);
+		prefs_box.append(&Label::new(Some("Voice for the learning language:")));
+		prefs_box.append(&voice_name_entry);
+		prefs_box.append(&Label::new(Some("Speaking rate:")));
+		prefs_box.append(&speech_rate_combo);
+		prefs_box.append(&Label::new(Some("After a wrong answer:")));
+		prefs_box.append(&retry_combo);
+		prefs_box.append(&Label::new(Some("Vocabulary opens:")));
+		prefs_box.append(&default_deck_combo);
+		prefs_box.append(&Label::new(Some("Questions per session:")));
+		prefs_box.append(&session_length_combo);
+		prefs_box.append(&Label::new(Some("Time per question in lightning rounds:")));
+		prefs_box.append(&time_limit_combo);
+		prefs_box.append(&back_btn_prefs);
+		app.stack.add_named(&prefs_box, Some("preferences"));
+
+		let direction_combo = ComboBoxText::new();
+		direction_combo.append(Some("normal"), "Normal");
+		direction_combo.append(Some("reverse"), "Reverse");
+		let exercise_combo = ComboBoxText::new();
+		exercise_combo.append(Some("translation"), "Vocabulary");
+		exercise_combo.append(Some("gender"), "Articles (der/die/das, el/la, le/la)");
+		exercise_combo.append(Some("conjugation"), "Conjugation");
+		exercise_combo.append(Some("listening"), "Listening (words with recordings)");
+		exercise_combo.append(Some("pictures"), "Pick the picture (words with pictures)");
+		let mode_combo = ComboBoxText::new();
+		mode_combo.append(Some("choice"), "Multiple choice");
+		mode_combo.append(Some("typed"), "Typing the answer");
+		let challenge_combo = ComboBoxText::new();
+		challenge_combo.append(Some("practice"), "Practice");
+		challenge_combo.append(
+			Some("lightning"),
+			&format!("Lightning round ({} timed questions)", settings::LIGHTNING_QUESTIONS),
+		);
+		challenge_combo.append(
+			Some("survival"),
+			&format!("Survival ({} lives, starts with {} choices)", settings::SURVIVAL_LIVES, settings::SURVIVAL_CHOICES),
+		);
+
+		let prefs = Prefs {
+			settings: app.settings.clone(),
+			profiles: app.profiles.clone(),
+			source_lang: source_lang_combo,
+			target_lang: target_lang_combo.clone(),
+			direction: direction_combo,
+			exercise: exercise_combo,
+			mode: mode_combo,
+			retry: retry_combo,
+			ignore_accents: ignore_accents_check,
+			optional_articles: optional_articles_check,
+			allow_typos: allow_typos_check,
+			show_romanization: show_romanization_check,
+			default_deck: default_deck_combo,
+			session_length: session_length_combo,
+			challenge: challenge_combo,
+			time_limit: time_limit_combo,
+			speak_prompts: speak_prompts_check,
+			voice_name: voice_name_entry,
+			speech_rate: speech_rate_combo,
+			voices: Rc::new(RefCell::new(BTreeMap::new())),
+			voice_lang: Rc::new(RefCell::new(None)),
+		};
+		prefs.apply(&app.languages.borrow());
+		{
+			let prefs = prefs.clone();
+			target_lang_combo.connect_changed(move |_| prefs.switch_voice());
+		}
+		{
+			let s = app.stack.clone();
+			let prefs = prefs.clone();
+			back_btn_prefs.connect_clicked(move |_| {
+				prefs.save();
+				s.set_visible_child_name("main_menu");
+			});
+		}
+		prefs
+	}
+
+	/// The settings chosen before each round, with their labels, for the
+	/// difficulty page.
+	pub fn round_settings(&self) -> [(&'static str, &ComboBoxText); 4] {
+		[
+			("Learning Direction:", &self.direction),
+			("Exercise:", &self.exercise),
+			("Answer by:", &self.mode),
+			("Play:", &self.challenge),
+		]
+	}
+
+	/// Offers only the languages `deck` can be studied in, or every known
+	/// language for `None`.
+	pub fn offer_languages(&self, languages: &Languages, deck: Option<&Deck>) {
+		fill_language_combos(&self.source_lang, &self.target_lang, languages, deck);
+	}
+
+	/// Offers a deck imported while the app runs, and any language it
+	/// brings, as defaults.
+	pub fn add_deck(&self, deck: &Deck, languages: &Languages) {
+		self.default_deck.append(Some(&deck.name), &deck.name);
+		self.offer_languages(languages, None);
+		self.apply(languages);
+	}
+
+	/// Sets every widget from the stored settings. The language combos must
+	/// already be filled; a saved language no deck offers any more is left
+	/// at the combo's current entry.
+	pub fn apply(&self, languages: &Languages) {
+		let settings = self.settings.borrow();
+		self.voice_lang.replace(None);
+		self.voices.replace(settings.voices.clone());
+		self.source_lang.set_active_id(Some(&settings.source_lang));
+		fill_target_combo(&self.source_lang, &self.target_lang, languages, None);
+		self.target_lang.set_active_id(Some(&settings.target_lang));
+		self.direction.set_active_id(Some(if settings.reverse { "reverse" } else { "normal" }));
+		self.exercise.set_active_id(Some(settings.exercise.name()));
+		self.mode.set_active_id(Some(match settings.mode {
+			QuizMode::MultipleChoice => "choice",
+			QuizMode::Typed => "typed",
+		}));
+		self.retry.set_active_id(Some(match settings.retry {
+			RetryPolicy::Now => "now",
+			RetryPolicy::Later => "later",
+			RetryPolicy::MoveOn => "move_on",
+		}));
+		self.ignore_accents.set_active(settings.tolerance.ignore_diacritics);
+		self.optional_articles.set_active(settings.tolerance.optional_articles);
+		self.allow_typos.set_active(settings.tolerance.max_typos > 0);
+		self.show_romanization.set_active(settings.show_romanization);
+		if !self.default_deck.set_active_id(settings.default_deck.as_deref()) {
+			self.default_deck.set_active_id(Some(""));
+		}
+		let length = settings.session_length.unwrap_or(0).to_string();
+		if !self.session_length.set_active_id(Some(&length)) {
+			self.session_length.set_active_id(Some("0"));
+		}
+		self.challenge.set_active_id(Some(match settings.challenge {
+			Challenge::Practice => "practice",
+			Challenge::Lightning => "lightning",
+			Challenge::Survival => "survival",
+		}));
+		if !self.time_limit.set_active_id(Some(&settings.time_limit.to_string())) {
+			self.time_limit.set_active_id(Some("5"));
+		}
+		self.speak_prompts.set_active(settings.speak_prompts);
+		self.switch_voice();
+	}
+
+	/// Keeps what the voice widgets say for the language they show, then
+	/// shows the voice of the learning language now selected.
+	pub fn switch_voice(&self) {
+		self.store_voice();
+		let Some(lang) = self.target_lang.active_id() else { return };
+		let voice = self.voices.borrow().get(lang.as_str()).cloned().unwrap_or_default();
+		self.voice_name.set_text(voice.name.as_deref().unwrap_or_default());
+		if !self.speech_rate.set_active_id(Some(&voice.rate.to_string())) {
+			self.speech_rate.set_active_id(Some("1"));
+		}
+		self.voice_lang.replace(Some(lang.to_string()));
+	}
+
+	fn store_voice(&self) {
+		let Some(lang) = self.voice_lang.borrow().clone() else { return };
+		let voice = Voice {
+			name: Some(self.voice_name.text().trim().to_string()).filter(|name| !name.is_empty()),
+			rate: self.speech_rate.active_id().and_then(|id| id.parse().ok()).unwrap_or(1.0),
+		};
+		let mut voices = self.voices.borrow_mut();
+		if voice == Voice::default() {
+			voices.remove(&lang);
+		} else {
+			voices.insert(lang, voice);
+		}
+	}
+
+	/// Copies the widgets into the stored settings and writes them out.
+	pub fn save(&self) {
+		let mut settings = self.settings.borrow_mut();
+		if let Some(id) = self.source_lang.active_id() {
+			settings.source_lang = id.to_string();
+		}
+		if let Some(id) = self.target_lang.active_id() {
+			settings.target_lang = id.to_string();
+		}
+		settings.reverse = self.direction.active_id().as_deref() == Some("reverse");
+		settings.exercise = match self.exercise.active_id().as_deref() {
+			Some("gender") => Exercise::Gender,
+			Some("conjugation") => Exercise::Conjugation,
+			Some("listening") => Exercise::Listening,
+			Some("pictures") => Exercise::Pictures,
+			_ => Exercise::Translation,
+		};
+		settings.mode = match self.mode.active_id().as_deref() {
+			Some("typed") => QuizMode::Typed,
+			_ => QuizMode::MultipleChoice,
+		};
+		settings.retry = match self.retry.active_id().as_deref() {
+			Some("later") => RetryPolicy::Later,
+			Some("move_on") => RetryPolicy::MoveOn,
+			_ => RetryPolicy::Now,
+		};
+		settings.tolerance.ignore_diacritics = self.ignore_accents.is_active();
+		settings.tolerance.optional_articles = self.optional_articles.is_active();
+		settings.tolerance.max_typos = match (self.allow_typos.is_active(), settings.tolerance.max_typos) {
+			(false, _) => 0,
+			(true, 0) => 1,
+			(true, n) => n,
+		};
+		settings.show_romanization = self.show_romanization.is_active();
+		settings.default_deck = self.default_deck.active_id()
+			.filter(|id| !id.is_empty())
+			.map(|id| id.to_string());
+		settings.session_length = self.session_length.active_id()
+			.and_then(|id| id.parse().ok())
+			.filter(|&n| n > 0);
+		settings.challenge = match self.challenge.active_id().as_deref() {
+			Some("lightning") => Challenge::Lightning,
+			Some("survival") => Challenge::Survival,
+			_ => Challenge::Practice,
+		};
+		if let Some(secs) = self.time_limit.active_id().and_then(|id| id.parse().ok()) {
+			settings.time_limit = secs;
+		}
+		settings.speak_prompts = self.speak_prompts.is_active();
+		self.store_voice();
+		settings.voices = self.voices.borrow().clone();
+		let path = paths::settings_file(&self.profiles.borrow().active().id);
+		if let Err(e) = settings.save(&path) {
+			eprintln!("corvid: cannot save settings: {}", e);
+		}
+	}
+
+	pub fn tolerance(&self) -> Tolerance {
+		self.settings.borrow().tolerance
+	}
+
+	pub fn shows_romanization(&self) -> bool {
+		self.show_romanization.is_active()
+	}
+}
+
+/// Replaces the entries of `combo` with `codes`, keeping the active entry
+/// when it is still offered and falling back to the first one otherwise.
+fn refill_combo(combo: &ComboBoxText, codes: &[String], languages: &Languages) {
+	let active = combo.active_id();
+	combo.remove_all();
+	for code in codes {
+		combo.append(Some(code), &languages.name(code));
+	}
+	if !active.is_some_and(|id| combo.set_active_id(Some(&id))) {
+		combo.set_active(Some(0));
+	}
+}
+
+/// Languages a deck can be studied in, or every known language before a
+/// topic is picked, sorted by name.
+fn offered_languages(languages: &Languages, deck: Option<&Deck>) -> Vec<String> {
+	match deck {
+		Some(deck) => {
+			let mut codes = deck.languages();
+			codes.sort_by_key(|c| languages.name(c));
+			codes
+		}
+		None => languages.all().iter().map(|l| l.code.clone()).collect(),
+	}
+}
+
+/// Offers as learning languages only those the deck pairs with the
+/// selected known language.
+fn fill_target_combo(source: &ComboBoxText, target: &ComboBoxText, languages: &Languages, deck: Option<&Deck>) {
+	let source = source.active_id().map(|id| id.to_string()).unwrap_or_default();
+	let codes: Vec<String> = offered_languages(languages, deck)
+		.into_iter()
+		.filter(|t| match deck {
+			Some(deck) => deck.covers(&source, t),
+			None => *t != source,
+		})
+		.collect();
+	refill_combo(target, &codes, languages);
+}
+
+fn fill_language_combos(source: &ComboBoxText, target: &ComboBoxText, languages: &Languages, deck: Option<&Deck>) {
+	let codes = offered_languages(languages, deck);
+	let sources: Vec<String> = codes
+		.iter()
+		.filter(|s| deck.is_none_or(|deck| codes.iter().any(|t| deck.covers(s, t))))
+		.cloned()
+		.collect();
+	refill_combo(source, &sources, languages);
+	fill_target_combo(source, target, languages, deck);
+}
@@ -0,0 +1,451 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, Entry, Label, MediaFile, Orientation, ProgressBar};
+use gtk::glib;
+use std::{cell::{Cell, RefCell}, rc::Rc, time::Duration};
+
+use corvid_core::language::Direction;
+use corvid_core::matcher::Verdict;
+use corvid_core::{Exercise, Game, Picture, QuizMode};
+
+use crate::prefs::Prefs;
+use crate::speech;
+use crate::summary_view::SummaryView;
+use crate::{App, feedback, save_progress};
+
+/// Pixel sizes of the picture above a prompt and of pictures on choice
+/// buttons.
+const PROMPT_PICTURE_SIZE: i32 = 128;
+const CHOICE_PICTURE_SIZE: i32 = 72;
+
+/// The quiz page and the result page shown after each answer.
+#[derive(Clone)]
+pub struct QuizView {
+	app: App,
+	prefs: Prefs,
+	summary: SummaryView,
+	word_label: Label,
+	speaker_btn: Button,
+	/// Holds the picture of the meaning asked about, if it has one.
+	picture_box: GtkBox,
+	/// The recording of the current question, kept so it plays to the end.
+	audio: Rc<RefCell<Option<MediaFile>>>,
+	reading_label: Label,
+	romanization_label: Label,
+	btns: Rc<RefCell<Vec<Button>>>,
+	correct_label: Label,
+	wrong_label: Label,
+	lives_label: Label,
+	answer_box: GtkBox,
+	answer_entry: Entry,
+	countdown: Countdown,
+	result_label: Label,
+}
+
+impl QuizView {
+	/// Builds the quiz and result pages. Sessions end on `summary`, which
+	/// can start a review of the missed words.
+	pub fn new(app: &App, prefs: &Prefs, summary: &SummaryView) -> Self {
+		let back_btn_quiz = Button::with_label("Back");
+		let word_label = Label::new(None);
+		// The reading sits right above the word, like ruby text.
+		let reading_label = Label::builder().visible(false).build();
+		let romanization_label = Label::builder().visible(false).build();
+		let word_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.margin_top(12)
+			.margin_bottom(12)
+			.margin_start(12)
+			.margin_end(12)
+			.build();
+		let picture_box = GtkBox::builder()
+			.halign(gtk::Align::Center)
+			.visible(false)
+			.build();
+		let speaker_btn = Button::builder()
+			.icon_name("audio-volume-high-symbolic")
+			.tooltip_text("Play the recording")
+			.visible(false)
+			.build();
+		let word_row = GtkBox::builder()
+			.orientation(Orientation::Horizontal)
+			.spacing(8)
+			.halign(gtk::Align::Center)
+			.build();
+		word_row.append(&word_label);
+		word_row.append(&speaker_btn);
+		word_box.append(&picture_box);
+		word_box.append(&reading_label);
+		word_box.append(&word_row);
+		word_box.append(&romanization_label);
+
+		back_btn_quiz.set_margin_top(12);
+		back_btn_quiz.set_margin_bottom(12);
+		back_btn_quiz.set_margin_start(12);
+		back_btn_quiz.set_margin_end(12);
+
+		let btns: Rc<RefCell<Vec<Button>>> = Rc::new(RefCell::new(
+			(0..7).map(|i| {
+				let b = Button::with_label(&format!("Choice {}", i + 1));
+				b.set_margin_top(12);
+				b.set_margin_bottom(12);
+				b.set_margin_start(12);
+				b.set_margin_end(12);
+				b
+			}).collect()
+		));
+
+		let quiz_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.vexpand(true)
+			.build();
+		let countdown_bar = ProgressBar::builder()
+			.margin_start(12)
+			.margin_end(12)
+			.visible(false)
+			.build();
+		quiz_box.append(&back_btn_quiz);
+		quiz_box.append(&countdown_bar);
+		quiz_box.append(&word_box);
+		for b in btns.borrow().iter() {
+			quiz_box.append(b);
+		}
+
+		let answer_entry = Entry::builder()
+			.placeholder_text("Type the translation")
+			.hexpand(true)
+			.build();
+		let check_btn = Button::with_label("Check");
+		let answer_box = GtkBox::builder()
+			.orientation(Orientation::Horizontal)
+			.spacing(8)
+			.margin_top(12)
+			.margin_bottom(12)
+			.margin_start(12)
+			.margin_end(12)
+			.visible(false)
+			.build();
+		answer_box.append(&answer_entry);
+		answer_box.append(&check_btn);
+		quiz_box.append(&answer_box);
+
+		let score_box = GtkBox::builder()
+			.orientation(Orientation::Horizontal)
+			.spacing(16)
+			.margin_top(12)
+			.margin_bottom(12)
+			.halign(gtk::Align::Center)
+			.hexpand(false)
+			.build();
+		let correct_label = Label::new(Some("Correct: 0"));
+		let wrong_label = Label::new(Some("Wrong:   0"));
+		let lives_label = Label::builder().visible(false).build();
+		score_box.append(&correct_label);
+		score_box.append(&wrong_label);
+		score_box.append(&lives_label);
+		quiz_box.append(&score_box);
+		app.stack.add_named(&quiz_box, Some("quiz_view"));
+
+		let result_label = Label::new(None);
+		let ok_btn = Button::with_label("OK");
+
+		result_label.set_margin_top(12);
+		result_label.set_margin_bottom(12);
+		result_label.set_margin_start(12);
+		result_label.set_margin_end(12);
+		ok_btn.set_margin_top(12);
+		ok_btn.set_margin_bottom(12);
+		ok_btn.set_margin_start(12);
+		ok_btn.set_margin_end(12);
+
+		let result_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.build();
+		result_box.append(&result_label);
+		result_box.append(&ok_btn);
+		app.stack.add_named(&result_box, Some("result_view"));
+
+		let view = QuizView {
+			app: app.clone(),
+			prefs: prefs.clone(),
+			summary: summary.clone(),
+			word_label,
+			speaker_btn: speaker_btn.clone(),
+			picture_box,
+			audio: Rc::new(RefCell::new(None)),
+			reading_label,
+			romanization_label,
+			btns: btns.clone(),
+			correct_label,
+			wrong_label,
+			lives_label,
+			answer_box,
+			answer_entry: answer_entry.clone(),
+			countdown: Countdown {
+				bar: countdown_bar,
+				serial: Rc::new(Cell::new(0)),
+			},
+			result_label,
+		};
+		{
+			let view = view.clone();
+			speaker_btn.connect_clicked(move |_| view.play_audio(&view.app.game.borrow()));
+		}
+		for (i, button) in btns.borrow().iter().enumerate() {
+			let view = view.clone();
+			button.connect_clicked(move |_| view.answer_choice(i));
+		}
+		{
+			let view = view.clone();
+			check_btn.connect_clicked(move |_| view.answer_typed());
+		}
+		{
+			let view = view.clone();
+			answer_entry.connect_activate(move |_| view.answer_typed());
+		}
+		{
+			let view = view.clone();
+			ok_btn.connect_clicked(move |_| view.ask_next());
+		}
+		{
+			let view = view.clone();
+			back_btn_quiz.connect_clicked(move |_| view.leave());
+		}
+		{
+			let view = view.clone();
+			summary.connect_review(move || view.review_missed());
+		}
+		view
+	}
+
+	/// Starts a session of `game` with its first question.
+	pub fn begin(&self, game: Game) {
+		self.app.game.replace(game);
+		self.ask_next();
+	}
+
+	/// Moves on to the next question, or to the summary once the session
+	/// is over, and starts the clock of timed questions.
+	fn ask_next(&self) {
+		let mut g = self.app.game.borrow_mut();
+		g.next_question();
+		if g.is_finished() {
+			self.summary.show(&g);
+			return;
+		}
+		self.show(&g);
+		drop(g);
+		let view = self.clone();
+		self.countdown.start(&self.app.game, Rc::new(move || view.time_out()));
+	}
+
+	/// A timed question that runs out counts as missed.
+	fn time_out(&self) {
+		let mut g = self.app.game.borrow_mut();
+		g.time_out();
+		save_progress(g.progress(), &self.app.profiles.borrow());
+		self.result_label.set_text(&feedback::timeout_text(&g));
+		self.app.stack.set_visible_child_name("result_view");
+	}
+
+	fn answer_choice(&self, i: usize) {
+		self.countdown.stop();
+		let mut g = self.app.game.borrow_mut();
+		// Picture choices have no label to read the answer from.
+		let chosen = g.current().and_then(|q| q.choices.get(i).cloned()).unwrap_or_default();
+		let correct = g.check_answer(i);
+		save_progress(g.progress(), &self.app.profiles.borrow());
+		let verdict = if correct { Verdict::Correct } else { Verdict::Wrong };
+		self.result_label.set_text(&feedback::result_text(&g, &chosen, verdict));
+		self.app.stack.set_visible_child_name("result_view");
+	}
+
+	fn answer_typed(&self) {
+		self.countdown.stop();
+		let tolerance = self.prefs.tolerance();
+		let mut g = self.app.game.borrow_mut();
+		let input = self.answer_entry.text();
+		let verdict = g.check_typed(&input, &tolerance);
+		save_progress(g.progress(), &self.app.profiles.borrow());
+		self.result_label.set_text(&feedback::result_text(&g, &input, verdict));
+		self.app.stack.set_visible_child_name("result_view");
+	}
+
+	/// Ends the session early, on its summary if anything was answered.
+	fn leave(&self) {
+		self.countdown.stop();
+		self.correct_label.set_text("Correct: 0");
+		self.wrong_label.set_text("Wrong:   0");
+		let g = self.app.game.borrow();
+		if g.summary().answered > 0 {
+			self.summary.show(&g);
+		} else {
+			self.app.stack.set_visible_child_name("topic_selection");
+		}
+	}
+
+	fn review_missed(&self) {
+		let review = self.app.game.borrow_mut().review_missed();
+		if let Some(review) = review {
+			self.begin(review);
+		}
+	}
+
+	/// Shows the game's current question and score on the quiz page.
+	fn show(&self, game: &Game) {
+		let score = game.score();
+		self.correct_label.set_text(&format!("Correct: {}", score.correct));
+		self.wrong_label.set_text(&format!("Wrong:   {}", score.wrong));
+		let lives = feedback::lives_text(game);
+		self.lives_label.set_text(lives.as_deref().unwrap_or_default());
+		self.lives_label.set_visible(lives.is_some());
+
+		let q = game.current();
+		let reading = q.and_then(|q| q.presented_reading.as_deref());
+		let reading_text = glib::markup_escape_text(reading.unwrap_or_default());
+		self.reading_label.set_markup(&format!("<small>{}</small>", reading_text));
+		self.reading_label.set_visible(reading.is_some());
+		let romanization = q.and_then(|q| q.presented_romanization.as_deref());
+		self.romanization_label.set_text(romanization.unwrap_or_default());
+		self.romanization_label.set_visible(romanization.is_some() && self.prefs.shows_romanization());
+		self.countdown.bar.set_visible(q.is_some() && game.time_limit().is_some());
+		self.countdown.bar.set_fraction(1.0);
+		while let Some(child) = self.picture_box.first_child() {
+			self.picture_box.remove(&child);
+		}
+		let picture = q.and_then(|q| q.presented_picture.as_ref());
+		if let Some(picture) = picture {
+			self.picture_box.append(&picture_widget(picture, PROMPT_PICTURE_SIZE));
+		}
+		self.picture_box.set_visible(picture.is_some());
+
+		let Some(q) = q else {
+			self.word_label.set_text("Nothing in this topic can be practised in this mode.");
+			self.answer_box.set_visible(false);
+			for button in self.btns.borrow().iter() {
+				button.hide();
+			}
+			self.app.stack.set_visible_child_name("quiz_view");
+			return;
+		};
+		let clip = q.presented_audio.as_deref().map(MediaFile::for_filename);
+		let speakable = self.app.speech.borrow().is_some() && game.spoken_prompt().is_some();
+		self.speaker_btn.set_visible(clip.is_some() || speakable);
+		self.audio.replace(clip);
+		if game.exercise() == Exercise::Listening {
+			// The prompt is the recording alone.
+			self.word_label.set_text("Listen, then answer with what you heard.");
+			self.word_label.set_direction(gtk::TextDirection::None);
+			self.play_audio(game);
+		} else {
+			self.word_label.set_text(&q.presented_word);
+			self.word_label.set_direction(text_direction(self.app.languages.borrow().direction(&q.language_code)));
+			if self.app.settings.borrow().speak_prompts {
+				self.play_audio(game);
+			}
+		}
+		self.answer_entry.set_direction(text_direction(self.app.languages.borrow().direction(game.answer_lang())));
+
+		let typed = game.mode() == QuizMode::Typed;
+		self.answer_box.set_visible(typed);
+		self.answer_entry.set_text("");
+
+		for (i, button) in self.btns.borrow().iter().enumerate() {
+			if !typed && i < q.choices.len() {
+				match q.choice_picture(&q.choices[i]) {
+					Some(picture) => button.set_child(Some(&picture_widget(picture, CHOICE_PICTURE_SIZE))),
+					None => button.set_label(&q.choices[i]),
+				}
+				button.show();
+			} else {
+				button.hide();
+			}
+		}
+		self.app.stack.set_visible_child_name("quiz_view");
+		if typed {
+			self.answer_entry.grab_focus();
+		}
+	}
+
+	/// Plays the current question's recording from the start, or reads the
+	/// prompt aloud when it has none.
+	fn play_audio(&self, game: &Game) {
+		if let Some(media) = self.audio.borrow().as_ref() {
+			media.seek(0);
+			media.play();
+		} else if let Some(engine) = self.app.speech.borrow_mut().as_mut() {
+			speech::speak_prompt(engine.as_mut(), game, &self.app.settings.borrow());
+		}
+	}
+}
+
+/// Runs the clock of timed questions.
+#[derive(Clone)]
+struct Countdown {
+	bar: ProgressBar,
+	/// Bumped whenever a question is answered or left, so that the timer
+	/// of an earlier question stops.
+	serial: Rc<Cell<u32>>,
+}
+
+impl Countdown {
+	fn stop(&self) {
+		self.serial.set(self.serial.get().wrapping_add(1));
+	}
+
+	/// Starts the clock for the current question of a timed game, emptying
+	/// the bar as time passes and calling `on_timeout` when it runs out.
+	fn start(&self, game: &Rc<RefCell<Game>>, on_timeout: Rc<dyn Fn()>) {
+		self.stop();
+		let Some(limit) = game.borrow().time_limit() else { return };
+		let serial = self.serial.get();
+		let countdown = self.clone();
+		let game = game.clone();
+		glib::timeout_add_local(Duration::from_millis(50), move || {
+			if countdown.serial.get() != serial {
+				return glib::ControlFlow::Break;
+			}
+			let Some(left) = game.borrow().time_left() else {
+				return glib::ControlFlow::Break;
+			};
+			countdown.bar.set_fraction(left.as_secs_f64() / limit.as_secs_f64());
+			if left.is_zero() {
+				countdown.stop();
+				on_timeout();
+				return glib::ControlFlow::Break;
+			}
+			glib::ControlFlow::Continue
+		});
+	}
+}
+
+/// A widget showing `picture` about `size` pixels across.
+fn picture_widget(picture: &Picture, size: i32) -> gtk::Widget {
+	match picture {
+		Picture::Image(path) => {
+			let image = gtk::Picture::for_filename(path);
+			image.set_can_shrink(true);
+			image.set_size_request(size, size);
+			image.upcast()
+		}
+		Picture::Emoji(emoji) => {
+			// Pango sizes are in points; at 96 dpi a point is 4/3 pixels.
+			let points = size * 3 / 4;
+			let label = Label::new(None);
+			label.set_markup(&format!(
+				"<span size=\"{}\">{}</span>",
+				points * gtk::pango::SCALE,
+				glib::markup_escape_text(emoji),
+			));
+			label.upcast()
+		}
+	}
+}
+
+fn text_direction(direction: Direction) -> gtk::TextDirection {
+	match direction {
+		Direction::LeftToRight => gtk::TextDirection::Ltr,
+		Direction::RightToLeft => gtk::TextDirection::Rtl,
+	}
+}
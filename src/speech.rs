@@ -0,0 +1,124 @@
+use std::{env, io, process::{Child, Command, Stdio}};
+
+use corvid_core::Game;
+use corvid_core::language;
+use corvid_core::settings::{Settings, Voice};
+
+/// Something that reads words aloud.
+pub trait SpeechBackend {
+	/// The engine's name, for messages.
+	fn name(&self) -> &'static str;
+
+	/// Starts saying `text` in `lang` without waiting for it to be said,
+	/// cutting off whatever is still being said.
+	fn speak(&mut self, text: &str, lang: &str, voice: &Voice) -> io::Result<()>;
+}
+
+/// speech-dispatcher, through its `spd-say` client.
+#[derive(Default)]
+pub struct SpeechDispatcher {
+	speaking: Option<Child>,
+}
+
+impl SpeechBackend for SpeechDispatcher {
+	fn name(&self) -> &'static str {
+		"speech-dispatcher"
+	}
+
+	fn speak(&mut self, text: &str, lang: &str, voice: &Voice) -> io::Result<()> {
+		// spd-say hands the text to the daemon and exits, so the previous
+		// one has normally finished and only needs reaping; --cancel below
+		// stops what the daemon is still saying.
+		stop(&mut self.speaking);
+		// Rates run from -100 to 100, with 0 as normal.
+		let rate = ((voice.rate - 1.0) * 100.0).round().clamp(-100.0, 100.0) as i32;
+		let mut command = Command::new("spd-say");
+		command.args(["--cancel", "--language", language::base(lang), "--rate", &rate.to_string()]);
+		if let Some(name) = &voice.name {
+			command.args(["--synthesis-voice", name]);
+		}
+		self.speaking = Some(quiet(command.arg("--").arg(text)).spawn()?);
+		Ok(())
+	}
+}
+
+impl Drop for SpeechDispatcher {
+	fn drop(&mut self) {
+		// Let the last word reach the daemon.
+		if let Some(mut child) = self.speaking.take() {
+			let _ = child.wait();
+		}
+	}
+}
+
+/// espeak-ng, started once per word.
+#[derive(Default)]
+pub struct EspeakNg {
+	speaking: Option<Child>,
+}
+
+/// espeak-ng's normal rate, in words per minute.
+const ESPEAK_WPM: f32 = 175.0;
+
+impl SpeechBackend for EspeakNg {
+	fn name(&self) -> &'static str {
+		"espeak-ng"
+	}
+
+	fn speak(&mut self, text: &str, lang: &str, voice: &Voice) -> io::Result<()> {
+		stop(&mut self.speaking);
+		// espeak-ng names its voices after language codes.
+		let name = voice.name.clone().unwrap_or_else(|| lang.to_lowercase());
+		let wpm = (ESPEAK_WPM * voice.rate).round().max(80.0) as u32;
+		let mut command = Command::new("espeak-ng");
+		command.args(["-v", &name, "-s", &wpm.to_string(), "--"]).arg(text);
+		self.speaking = Some(quiet(&mut command).spawn()?);
+		Ok(())
+	}
+}
+
+impl Drop for EspeakNg {
+	fn drop(&mut self) {
+		stop(&mut self.speaking);
+	}
+}
+
+/// Reaps the process of the previous word, ending it first if it is
+/// still speaking.
+fn stop(speaking: &mut Option<Child>) {
+	if let Some(mut child) = speaking.take()
+		&& !matches!(child.try_wait(), Ok(Some(_)))
+	{
+		let _ = child.kill();
+		let _ = child.wait();
+	}
+}
+
+fn quiet(command: &mut Command) -> &mut Command {
+	command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null())
+}
+
+/// The first speech engine installed, preferring speech-dispatcher, or
+/// `None` when there is none and words are simply not read aloud.
+pub fn detect() -> Option<Box<dyn SpeechBackend>> {
+	if on_path("spd-say") {
+		Some(Box::new(SpeechDispatcher::default()))
+	} else if on_path("espeak-ng") {
+		Some(Box::new(EspeakNg::default()))
+	} else {
+		None
+	}
+}
+
+fn on_path(program: &str) -> bool {
+	env::var_os("PATH").is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+/// Reads the current prompt of `game` aloud in the voice `settings` give
+/// its language.
+pub fn speak_prompt(engine: &mut dyn SpeechBackend, game: &Game, settings: &Settings) {
+	let Some((text, lang)) = game.spoken_prompt() else { return };
+	if let Err(e) = engine.speak(text, lang, &settings.voice(lang)) {
+		eprintln!("corvid: cannot speak with {}: {}", engine.name(), e);
+	}
+}
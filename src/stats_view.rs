@@ -0,0 +1,140 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, DrawingArea, Label, Orientation, PolicyType, ScrolledWindow};
+use std::{cell::RefCell, rc::Rc};
+
+use corvid_core::scheduler;
+use corvid_core::stats::{self, DayCount};
+
+use crate::{App, charts};
+
+/// The statistics page, drawn from the learner's progress when opened.
+#[derive(Clone)]
+pub struct StatsView {
+	app: App,
+	days: Rc<RefCell<Vec<DayCount>>>,
+	topics: Rc<RefCell<Vec<charts::TopicMastery>>>,
+	reviews_chart: DrawingArea,
+	accuracy_chart: DrawingArea,
+	mastery_chart: DrawingArea,
+	calendar_chart: DrawingArea,
+}
+
+impl StatsView {
+	pub fn new(app: &App) -> Self {
+		let stats_days: Rc<RefCell<Vec<DayCount>>> = Rc::new(RefCell::new(Vec::new()));
+		let stats_topics: Rc<RefCell<Vec<charts::TopicMastery>>> = Rc::new(RefCell::new(Vec::new()));
+		let reviews_chart = DrawingArea::builder().content_height(160).build();
+		let accuracy_chart = DrawingArea::builder().content_height(160).build();
+		let mastery_chart = DrawingArea::builder()
+			.content_height(((app.decks.borrow().len() + 1) as f64 * charts::MASTERY_ROW) as i32)
+			.build();
+		let calendar_chart = DrawingArea::builder().content_height(160).build();
+		{
+			let days = stats_days.clone();
+			reviews_chart.set_draw_func(move |_, cr, width, height| {
+				let drawn = charts::reviews_per_day(cr, width.into(), height.into(), &days.borrow(), scheduler::today());
+				if let Err(e) = drawn {
+					eprintln!("corvid: cannot draw chart: {}", e);
+				}
+			});
+		}
+		{
+			let days = stats_days.clone();
+			accuracy_chart.set_draw_func(move |_, cr, width, height| {
+				let drawn = charts::accuracy_over_time(cr, width.into(), height.into(), &days.borrow(), scheduler::today());
+				if let Err(e) = drawn {
+					eprintln!("corvid: cannot draw chart: {}", e);
+				}
+			});
+		}
+		{
+			let topics = stats_topics.clone();
+			mastery_chart.set_draw_func(move |_, cr, width, _| {
+				if let Err(e) = charts::mastery_by_topic(cr, width.into(), &topics.borrow()) {
+					eprintln!("corvid: cannot draw chart: {}", e);
+				}
+			});
+		}
+		{
+			let days = stats_days.clone();
+			calendar_chart.set_draw_func(move |_, cr, width, height| {
+				let drawn = charts::study_calendar(cr, width.into(), height.into(), &days.borrow(), scheduler::today());
+				if let Err(e) = drawn {
+					eprintln!("corvid: cannot draw chart: {}", e);
+				}
+			});
+		}
+		let back_btn_stats = Button::with_label("Back");
+		back_btn_stats.set_margin_top(12);
+		back_btn_stats.set_margin_bottom(12);
+		back_btn_stats.set_margin_start(12);
+		back_btn_stats.set_margin_end(12);
+		let stats_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.margin_start(12)
+			.margin_end(12)
+			.margin_bottom(12)
+			.build();
+		for (title, chart) in [
+			("Reviews per day", &reviews_chart),
+			("Accuracy over time", &accuracy_chart),
+			("Words by mastery", &mastery_chart),
+			("Study days", &calendar_chart),
+		] {
+			let title = Label::new(Some(title));
+			title.set_halign(gtk::Align::Start);
+			title.set_margin_top(12);
+			stats_box.append(&title);
+			stats_box.append(chart);
+		}
+		let stats_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.min_content_width(360)
+			.vexpand(true)
+			.child(&stats_box)
+			.build();
+		let stats_container = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.hexpand(true)
+			.vexpand(true)
+			.build();
+		stats_container.append(&back_btn_stats);
+		stats_container.append(&stats_scroll);
+		app.stack.add_named(&stats_container, Some("statistics"));
+
+		{
+			let s = app.stack.clone();
+			back_btn_stats.connect_clicked(move |_| {
+				s.set_visible_child_name("main_menu");
+			});
+		}
+		StatsView {
+			app: app.clone(),
+			days: stats_days,
+			topics: stats_topics,
+			reviews_chart,
+			accuracy_chart,
+			mastery_chart,
+			calendar_chart,
+		}
+	}
+
+	/// Redraws the charts from the current progress and shows them.
+	pub fn show(&self) {
+		let g = self.app.game.borrow();
+		self.days.replace(stats::daily(g.progress()));
+		let decks = self.app.decks.borrow();
+		self.mastery_chart.set_content_height(((decks.len() + 1) as f64 * charts::MASTERY_ROW) as i32);
+		self.topics.replace(
+			decks
+				.iter()
+				.map(|d| (d.name.clone(), stats::mastery(g.progress(), &d.meanings)))
+				.collect(),
+		);
+		for chart in [&self.reviews_chart, &self.accuracy_chart, &self.mastery_chart, &self.calendar_chart] {
+			chart.queue_draw();
+		}
+		self.app.stack.set_visible_child_name("statistics");
+	}
+}
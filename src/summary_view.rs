@@ -0,0 +1,124 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, Label, Orientation, PolicyType, ScrolledWindow, Stack};
+use std::{cell::RefCell, rc::Rc};
+
+use corvid_core::highscores::{HighScore, HighScores};
+use corvid_core::language::Languages;
+use corvid_core::profile::Profiles;
+use corvid_core::storage;
+use corvid_core::Game;
+
+use crate::{App, feedback, paths};
+
+/// The end-of-session page.
+#[derive(Clone)]
+pub struct SummaryView {
+	stack: Stack,
+	stats_label: Label,
+	missed_label: Label,
+	high_scores_label: Label,
+	review_btn: Button,
+	high_scores: Rc<RefCell<HighScores>>,
+	profiles: Rc<RefCell<Profiles>>,
+	languages: Rc<RefCell<Languages>>,
+}
+
+impl SummaryView {
+	pub fn new(app: &App) -> Self {
+		let stats_label = Label::new(None);
+		let high_scores_label = Label::builder()
+			.halign(gtk::Align::Start)
+			.margin_start(12)
+			.margin_end(12)
+			.visible(false)
+			.build();
+		let missed_label = Label::builder()
+			.halign(gtk::Align::Start)
+			.selectable(true)
+			.build();
+		let review_btn = Button::with_label("Review missed words");
+		let done_btn = Button::with_label("Done");
+		for btn in &[&review_btn, &done_btn] {
+			btn.set_margin_top(12);
+			btn.set_margin_bottom(12);
+			btn.set_margin_start(12);
+			btn.set_margin_end(12);
+		}
+		let missed_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.vexpand(true)
+			.margin_start(12)
+			.margin_end(12)
+			.child(&missed_label)
+			.build();
+		let summary_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.margin_top(12)
+			.build();
+		summary_box.append(&Label::new(Some("Session summary")));
+		summary_box.append(&stats_label);
+		summary_box.append(&high_scores_label);
+		summary_box.append(&missed_scroll);
+		summary_box.append(&review_btn);
+		summary_box.append(&done_btn);
+		app.stack.add_named(&summary_box, Some("summary_view"));
+
+		{
+			let s = app.stack.clone();
+			done_btn.connect_clicked(move |_| {
+				s.set_visible_child_name("topic_selection");
+			});
+		}
+		SummaryView {
+			stack: app.stack.clone(),
+			stats_label,
+			missed_label,
+			high_scores_label,
+			review_btn,
+			high_scores: Rc::new(RefCell::new(paths::load_highscores())),
+			profiles: app.profiles.clone(),
+			languages: app.languages.clone(),
+		}
+	}
+
+	/// Calls `review` when the learner asks to go over the missed words.
+	pub fn connect_review(&self, review: impl Fn() + 'static) {
+		self.review_btn.connect_clicked(move |_| review());
+	}
+
+	pub fn show(&self, game: &Game) {
+		let summary = game.summary();
+		self.stats_label.set_text(&feedback::summary_text(&summary));
+
+		// Only complete lightning rounds and survival runs go into the
+		// high scores.
+		let ranked = (game.time_limit().is_some() || game.lives().is_some()) && game.is_finished();
+		self.high_scores_label.set_visible(ranked);
+		if ranked {
+			let key = HighScores::key_for(game);
+			let mut scores = self.high_scores.borrow_mut();
+			let place = scores.submit(&key, HighScore {
+				name: self.profiles.borrow().active().name.clone(),
+				points: summary.points.unwrap_or(0),
+				correct: summary.correct,
+				answered: summary.answered,
+				at: storage::now_secs(),
+			});
+			if let Err(e) = scores.save(&paths::highscores_file()) {
+				eprintln!("corvid: cannot save high scores: {}", e);
+			}
+			let title = HighScores::title_for(game, &self.languages.borrow());
+			self.high_scores_label.set_text(&feedback::highscore_text(&title, scores.table(&key), place));
+		}
+
+		let missed: Vec<String> = summary.missed.iter().map(feedback::missed_text).collect();
+		if missed.is_empty() {
+			self.missed_label.set_text("Nothing missed.");
+		} else {
+			self.missed_label.set_text(&format!("Missed:\n{}", missed.join("\n")));
+		}
+		self.review_btn.set_sensitive(!missed.is_empty());
+		self.stack.set_visible_child_name("summary_view");
+	}
+}
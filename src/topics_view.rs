@@ -0,0 +1,88 @@
+use gtk::prelude::*;
+use gtk::{Box as GtkBox, Button, Label, Orientation, PolicyType, ScrolledWindow};
+
+use corvid_core::deck::Deck;
+
+use crate::difficulty_view::DifficultyView;
+use crate::{App, paths};
+
+/// The list of topics, one button per deck.
+#[derive(Clone)]
+pub struct TopicsView {
+	topic_box: GtkBox,
+	/// Says where decks are looked for while there are none.
+	empty_label: Label,
+	difficulty_view: DifficultyView,
+}
+
+impl TopicsView {
+	pub fn new(app: &App, difficulty_view: &DifficultyView) -> Self {
+		let back_btn_topic = Button::with_label("Back");
+		back_btn_topic.set_margin_top(12);
+		back_btn_topic.set_margin_bottom(12);
+		back_btn_topic.set_margin_start(12);
+		back_btn_topic.set_margin_end(12);
+		let topic_box = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.spacing(8)
+			.vexpand(true)
+			.build();
+		let dirs: Vec<String> = paths::deck_dirs()
+			.iter()
+			.map(|d| d.display().to_string())
+			.collect();
+		let empty_label = Label::builder()
+			.label(format!("No decks found in:\n{}", dirs.join("\n")))
+			.margin_top(12)
+			.margin_start(12)
+			.margin_end(12)
+			.visible(app.decks.borrow().is_empty())
+			.build();
+		topic_box.append(&empty_label);
+		let topic_scroll = ScrolledWindow::builder()
+			.hscrollbar_policy(PolicyType::Never)
+			.min_content_width(360)
+			.vexpand(true)
+			.child(&topic_box)
+			.build();
+		let topic_container = GtkBox::builder()
+			.orientation(Orientation::Vertical)
+			.hexpand(true)
+			.vexpand(true)
+			.build();
+		topic_container.append(&back_btn_topic);
+		topic_container.append(&topic_scroll);
+		app.stack.add_named(&topic_container, Some("topic_selection"));
+
+		{
+			let s = app.stack.clone();
+			back_btn_topic.connect_clicked(move |_| {
+				s.set_visible_child_name("main_menu");
+			});
+		}
+		let view = TopicsView {
+			topic_box,
+			empty_label,
+			difficulty_view: difficulty_view.clone(),
+		};
+		for deck in app.decks.borrow().iter() {
+			view.add_deck(deck);
+		}
+		view
+	}
+
+	/// Lists `deck`, which opens its difficulty page.
+	pub fn add_deck(&self, deck: &Deck) {
+		let btn = Button::with_label(&deck.name);
+		btn.set_tooltip_text(Some(&deck.path.display().to_string()));
+		btn.set_margin_top(12);
+		btn.set_margin_bottom(12);
+		btn.set_margin_start(12);
+		btn.set_margin_end(12);
+		let difficulty_view = self.difficulty_view.clone();
+		let deck = deck.clone();
+		btn.connect_clicked(move |_| difficulty_view.open(&deck));
+		self.topic_box.append(&btn);
+		self.empty_label.set_visible(false);
+	}
+}
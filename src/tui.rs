@@ -0,0 +1,352 @@
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use corvid_core::deck::{self, Deck};
+use corvid_core::highscores::{HighScore, HighScores};
+use corvid_core::language::Languages;
+use corvid_core::matcher::Verdict;
+use corvid_core::settings::{self, Challenge, Settings};
+use corvid_core::storage;
+use corvid_core::{Exercise, Game, Picture, QuizMode, RetryPolicy, SessionConfig};
+
+use crate::speech::{self, SpeechBackend};
+use crate::{feedback, paths};
+
+const DIFFICULTIES: [(&str, u32); 3] = [("Easy", 3), ("Medium", 5), ("Hard", 7)];
+
+/// `corvid tui`: the same quizzes as the window, read from and written to
+/// the terminal. The profile, topics and settings are picked from numbered
+/// menus and answers are given by number or, in typed mode, by typing them.
+/// Prompts are read aloud when a speech engine is installed and the
+/// learner asked for it.
+pub fn run() -> bool {
+	let (decks, deck_errors) = deck::load_decks(&paths::deck_dirs());
+	for e in &deck_errors {
+		eprintln!("corvid: skipping deck {}", e);
+	}
+	if decks.is_empty() {
+		eprintln!("corvid: no decks found");
+		return false;
+	}
+
+	let languages = Languages::from_decks(&decks);
+	let mut input = Input::new();
+	let mut profiles = paths::open_profiles();
+	if profiles.all().len() > 1 {
+		let names: Vec<&str> = profiles.all().iter().map(|p| p.name.as_str()).collect();
+		let Some(i) = input.choose("Who is playing", &names) else { return true };
+		let id = profiles.all()[i].id.clone();
+		profiles.set_active(&id).expect("picked from the list");
+		if let Err(e) = profiles.save(&paths::profiles_file()) {
+			eprintln!("corvid: cannot save profiles: {}", e);
+		}
+	}
+
+	let profile = &profiles.active().id;
+	let progress_path = paths::progress_file(profile);
+	let mut progress = paths::load_progress(profile);
+	let settings_path = paths::settings_file(profile);
+	let mut settings = paths::load_settings(profile);
+	let mut saved = settings_path.exists();
+	let mut speech = speech::detect();
+
+	loop {
+		println!();
+		let names: Vec<&str> = decks.iter().map(|d| d.name.as_str()).collect();
+		let Some(deck) = input.choose("Topic", &names) else { break };
+		let can_listen = speech.is_some();
+		let Some(mut config) = configure(&mut input, &decks[deck], &languages, &mut settings, saved, can_listen) else {
+			break;
+		};
+		config.speech = can_listen;
+		match settings.save(&settings_path) {
+			Ok(()) => saved = true,
+			Err(e) => eprintln!("corvid: cannot save settings: {}", e),
+		}
+
+		let mut game = Game::new(config, decks[deck].meanings.clone(), progress);
+		let mut finished = quiz(&mut input, &mut game, &settings, &mut speech, &progress_path);
+		if (game.time_limit().is_some() || game.lives().is_some()) && game.is_finished() {
+			enter_high_score(&game, &profiles.active().name, &languages);
+		}
+		while finished && summarize(&mut input, &game) {
+			let Some(review) = game.review_missed() else { break };
+			game = review;
+			finished = quiz(&mut input, &mut game, &settings, &mut speech, &progress_path);
+		}
+		progress = game.take_progress();
+		if !finished {
+			break;
+		}
+	}
+	true
+}
+
+/// Picks a language from `codes` by its display name.
+fn choose_language(input: &mut Input, title: &str, codes: &[String], languages: &Languages) -> Option<String> {
+	let names: Vec<String> = codes.iter().map(|c| languages.name(c)).collect();
+	let names: Vec<&str> = names.iter().map(String::as_str).collect();
+	Some(codes[input.choose(title, &names)?].clone())
+}
+
+/// Asks for the session settings, offering the `saved` ones first when the
+/// deck covers their languages, and stores the answers in `settings`.
+fn configure(
+	input: &mut Input,
+	deck: &Deck,
+	languages: &Languages,
+	settings: &mut Settings,
+	saved: bool,
+	can_listen: bool,
+) -> Option<SessionConfig> {
+	// The terminal cannot play recordings, so listening needs a speech
+	// engine to say the words. Nor can it show pictures to pick from.
+	let playable = match settings.exercise {
+		Exercise::Listening => can_listen,
+		Exercise::Pictures => false,
+		_ => true,
+	};
+	if saved
+		&& playable
+		&& deck.covers(&settings.source_lang, &settings.target_lang)
+	{
+		let last = settings.session_config(&deck.name);
+		let summary = format!(
+			"Same as last time ({} to {})",
+			languages.name(&last.source_lang),
+			languages.name(&last.target_lang),
+		);
+		if input.choose("Settings", &[summary.as_str(), "Change them"])? == 0 {
+			return Some(last);
+		}
+	}
+
+	// Only pairs the deck has at least one meaning for are offered.
+	let mut codes = deck.languages();
+	codes.sort_by_key(|c| languages.name(c));
+	let sources: Vec<String> = codes
+		.iter()
+		.filter(|s| codes.iter().any(|t| deck.covers(s, t)))
+		.cloned()
+		.collect();
+	if sources.is_empty() {
+		println!("{} has no language pair to practise.", deck.name);
+		return None;
+	}
+	settings.source_lang = choose_language(input, "Known language", &sources, languages)?;
+	let targets: Vec<String> = codes.into_iter().filter(|t| deck.covers(&settings.source_lang, t)).collect();
+	settings.target_lang = choose_language(input, "Learning language", &targets, languages)?;
+	let mut exercises = vec!["Vocabulary", "Articles", "Conjugation"];
+	if can_listen {
+		exercises.push("Listening");
+	}
+	settings.exercise = match input.choose("Exercise", &exercises)? {
+		1 => Exercise::Gender,
+		2 => Exercise::Conjugation,
+		3 => Exercise::Listening,
+		_ => Exercise::Translation,
+	};
+	settings.reverse = settings.exercise == Exercise::Translation
+		&& input.choose("Direction", &["Normal", "Reverse"])? == 1;
+	settings.mode = match input.choose("Answer by", &["Multiple choice", "Typing the answer"])? {
+		1 => QuizMode::Typed,
+		_ => QuizMode::MultipleChoice,
+	};
+	if settings.mode == QuizMode::MultipleChoice {
+		let labels: Vec<String> = DIFFICULTIES
+			.iter()
+			.map(|(name, n)| format!("{} ({} choices)", name, n))
+			.collect();
+		let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+		settings.num_choices = DIFFICULTIES[input.choose("Difficulty", &labels)?].1;
+	}
+	let retry_options = ["Ask it again right away", "Ask it again a little later", "Move on"];
+	settings.retry = match input.choose("After a wrong answer", &retry_options)? {
+		1 => RetryPolicy::Later,
+		2 => RetryPolicy::MoveOn,
+		_ => RetryPolicy::Now,
+	};
+	let lightning = format!(
+		"Lightning round ({} questions, {} s each)",
+		settings::LIGHTNING_QUESTIONS, settings.time_limit,
+	);
+	let survival = format!("Survival ({} lives)", settings::SURVIVAL_LIVES);
+	settings.challenge = match input.choose("Play", &["Practice", lightning.as_str(), survival.as_str()])? {
+		1 => Challenge::Lightning,
+		2 => Challenge::Survival,
+		_ => Challenge::Practice,
+	};
+
+	Some(settings.session_config(&deck.name))
+}
+
+/// Enters a finished lightning round or survival run into the high scores
+/// and prints its table.
+fn enter_high_score(game: &Game, name: &str, languages: &Languages) {
+	let path = paths::highscores_file();
+	let mut scores = paths::load_highscores();
+	let summary = game.summary();
+	let key = HighScores::key_for(game);
+	let place = scores.submit(&key, HighScore {
+		name: name.to_string(),
+		points: summary.points.unwrap_or(0),
+		correct: summary.correct,
+		answered: summary.answered,
+		at: storage::now_secs(),
+	});
+	if let Err(e) = scores.save(&path) {
+		eprintln!("corvid: cannot save high scores: {}", e);
+	}
+	let title = format!("{}:", HighScores::title_for(game, languages));
+	println!("\n{}", feedback::highscore_text(&title, scores.table(&key), place));
+}
+
+/// Prints how the session went and asks whether to review the missed
+/// words. Returns true when the learner wants to.
+fn summarize(input: &mut Input, game: &Game) -> bool {
+	let summary = game.summary();
+	if summary.answered == 0 {
+		return false;
+	}
+	println!("\n{}", feedback::summary_text(&summary));
+	if summary.missed.is_empty() {
+		return false;
+	}
+	println!("Missed:");
+	for q in &summary.missed {
+		println!("  {}", feedback::missed_text(q));
+	}
+	input.choose("Review the missed words", &["Yes", "No"]) == Some(0)
+}
+
+/// Runs one session until it is complete or the learner leaves it with an
+/// empty answer. Returns false when input has ended.
+fn quiz(
+	input: &mut Input,
+	game: &mut Game,
+	settings: &Settings,
+	speech: &mut Option<Box<dyn SpeechBackend>>,
+	progress_path: &Path,
+) -> bool {
+	println!("\nAnswer with an empty line to go back to the topics.");
+
+	if game.next_question().is_none() {
+		println!("Nothing in this topic can be practised in this mode.");
+		return true;
+	}
+	loop {
+		let score = game.score();
+		let Some(q) = game.current() else { return true };
+		match feedback::lives_text(game) {
+			Some(lives) => println!("\nCorrect: {}  Wrong: {}  {}", score.correct, score.wrong, lives),
+			None => println!("\nCorrect: {}  Wrong: {}", score.correct, score.wrong),
+		}
+		let listening = game.exercise() == Exercise::Listening;
+		if listening {
+			println!("(listen)");
+		} else {
+			if let Some(Picture::Emoji(emoji)) = &q.presented_picture {
+				println!("{}", emoji);
+			}
+			if let Some(reading) = &q.presented_reading {
+				println!("{}", reading);
+			}
+			match &q.presented_romanization {
+				Some(romanization) => println!("{}  ({})", q.presented_word, romanization),
+				None => println!("{}", q.presented_word),
+			}
+		}
+		if (listening || settings.speak_prompts)
+			&& let Some(engine) = speech.as_mut()
+		{
+			speech::speak_prompt(engine.as_mut(), game, settings);
+		}
+		if let Some(limit) = game.time_limit() {
+			println!("(answer within {} s)", limit.as_secs());
+		}
+
+		// Some(index) for a choice, None for a typed answer.
+		let (chosen, choice) = if game.mode() == QuizMode::Typed {
+			let Some(answer) = input.line("> ") else { return false };
+			if answer.is_empty() {
+				return true;
+			}
+			(answer, None)
+		} else {
+			for (i, choice) in q.choices.iter().enumerate() {
+				println!("  {}) {}", i + 1, choice);
+			}
+			let count = q.choices.len();
+			let choice = loop {
+				let Some(answer) = input.line("> ") else { return false };
+				if answer.is_empty() {
+					return true;
+				}
+				match answer.parse::<usize>() {
+					Ok(n) if (1..=count).contains(&n) => break n - 1,
+					_ => println!("Enter a number from 1 to {}.", count),
+				}
+			};
+			(q.choices[choice].clone(), Some(choice))
+		};
+		// The terminal cannot interrupt the learner, so a late answer is
+		// only found out once it is given.
+		if game.time_left() == Some(Duration::ZERO) {
+			game.time_out();
+			println!("{}", feedback::timeout_text(game));
+		} else {
+			let verdict = match choice {
+				Some(i) if game.check_answer(i) => Verdict::Correct,
+				Some(_) => Verdict::Wrong,
+				None => game.check_typed(&chosen, &settings.tolerance),
+			};
+			println!("{}", feedback::result_text(game, &chosen, verdict));
+		}
+
+		if let Err(e) = game.progress().save(progress_path) {
+			eprintln!("corvid: cannot save progress: {}", e);
+		}
+		if game.next_question().is_none() && game.is_finished() {
+			println!("\nSession complete.");
+			return true;
+		}
+	}
+}
+
+struct Input {
+	stdin: io::StdinLock<'static>,
+}
+
+impl Input {
+	fn new() -> Self {
+		Self { stdin: io::stdin().lock() }
+	}
+
+	/// Reads one trimmed line, or `None` at end of input.
+	fn line(&mut self, prompt: &str) -> Option<String> {
+		print!("{}", prompt);
+		let _ = io::stdout().flush();
+		let mut line = String::new();
+		match self.stdin.read_line(&mut line) {
+			Ok(0) | Err(_) => None,
+			Ok(_) => Some(line.trim().to_string()),
+		}
+	}
+
+	/// Shows a numbered menu and returns the index picked, or `None` at
+	/// end of input.
+	fn choose(&mut self, title: &str, options: &[&str]) -> Option<usize> {
+		println!("{}:", title);
+		for (i, option) in options.iter().enumerate() {
+			println!("  {}) {}", i + 1, option);
+		}
+		loop {
+			let answer = self.line("> ")?;
+			match answer.parse::<usize>() {
+				Ok(n) if (1..=options.len()).contains(&n) => return Some(n - 1),
+				_ => println!("Enter a number from 1 to {}.", options.len()),
+			}
+		}
+	}
+}
@@ -0,0 +1,77 @@
+use std::{fs, path::PathBuf};
+
+use corvid_core::validate::{self, Severity};
+
+use crate::paths;
+
+/// `corvid validate [deck...]`. Each argument is a deck file or the file
+/// stem of a deck in one of the deck directories; without arguments every
+/// deck is checked. Returns false when any deck has errors.
+pub fn run(args: &[String]) -> bool {
+	let paths: Vec<PathBuf> = if args.is_empty() {
+		all_deck_files()
+	} else {
+		args.iter().map(|arg| resolve(arg)).collect()
+	};
+	if paths.is_empty() {
+		eprintln!("corvid: no decks found");
+		return false;
+	}
+
+	let (mut errors, mut warnings) = (0, 0);
+	for path in &paths {
+		let issues = match validate::check_file(path) {
+			Ok(issues) => issues,
+			Err(e) => {
+				eprintln!("{}: error: {}", path.display(), e);
+				errors += 1;
+				continue;
+			}
+		};
+		for issue in issues {
+			let label = match issue.severity {
+				Severity::Error => {
+					errors += 1;
+					"error"
+				}
+				Severity::Warning => {
+					warnings += 1;
+					"warning"
+				}
+			};
+			println!("{}:{}: {}: {}", path.display(), issue.line, label, issue.message);
+		}
+	}
+	println!("{} deck(s) checked, {} error(s), {} warning(s)", paths.len(), errors, warnings);
+	errors == 0
+}
+
+fn all_deck_files() -> Vec<PathBuf> {
+	let mut files = Vec::new();
+	for dir in paths::deck_dirs() {
+		let Ok(entries) = fs::read_dir(&dir) else { continue };
+		let mut found: Vec<PathBuf> = entries
+			.filter_map(|e| e.ok().map(|e| e.path()))
+			.filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+			.collect();
+		found.sort();
+		files.extend(found);
+	}
+	files
+}
+
+/// Treats `arg` as a path when it exists, otherwise as the name of a deck
+/// file in the deck directories, preferring the user's copy.
+fn resolve(arg: &str) -> PathBuf {
+	let path = PathBuf::from(arg);
+	if path.exists() {
+		return path;
+	}
+	paths::deck_dirs()
+		.into_iter()
+		.rev()
+		.map(|dir| dir.join(format!("{}.toml", arg)))
+		.find(|p| p.exists())
+		.unwrap_or(path)
+}
+